@@ -0,0 +1,53 @@
+//! Embeds the vendor public key that signed licenses, revocation lists and
+//! server responses are checked against.
+//!
+//! Release builds set `CYBERVAULT_VENDOR_PUBLIC_KEY` to the hex-encoded
+//! Ed25519 public key. With the `dev-key` feature and no variable set, the
+//! local development key in `keys/vendor_dev.pub` is used instead (see
+//! `keys/README.md`). Without either, the build trusts no key and rejects
+//! every signature.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const KEY_VAR: &str = "CYBERVAULT_VENDOR_PUBLIC_KEY";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={}", KEY_VAR);
+    let manifest = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("cargo sets CARGO_MANIFEST_DIR"));
+    let dev_key = manifest.join("../keys/vendor_dev.pub");
+
+    let key = match env::var(KEY_VAR) {
+        Ok(hex) => Some(parse_key(&hex).unwrap_or_else(|err| panic!("{}: {}", KEY_VAR, err))),
+        Err(_) if env::var_os("CARGO_FEATURE_DEV_KEY").is_some() => {
+            println!("cargo:rerun-if-changed={}", dev_key.display());
+            match fs::read_to_string(&dev_key) {
+                Ok(hex) => {
+                    println!("cargo:warning=trusting the development vendor key in keys/vendor_dev.pub");
+                    Some(parse_key(&hex).unwrap_or_else(|err| panic!("{}: {}", dev_key.display(), err)))
+                }
+                Err(_) => {
+                    println!("cargo:warning=dev-key: keys/vendor_dev.pub not found; run `license_keygen signing-key`");
+                    None
+                }
+            }
+        }
+        Err(_) => None,
+    };
+
+    let out = PathBuf::from(env::var("OUT_DIR").expect("cargo sets OUT_DIR")).join("vendor_key.rs");
+    let value = match key {
+        Some(key) => format!("Some({:?})", key),
+        None => "None".to_string(),
+    };
+    fs::write(&out, format!("pub const VENDOR_PUBLIC_KEY: Option<[u8; 32]> = {};\n", value))
+        .expect("cannot write vendor key");
+}
+
+fn parse_key(text: &str) -> Result<[u8; 32], String> {
+    hex::decode(text.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| "expected a 32-byte hex-encoded Ed25519 public key".to_string())
+}
@@ -0,0 +1,375 @@
+//! Challenge packs: a sequence of levels, each checked by one of several
+//! verification algorithms.
+//!
+//! A pack is a JSON file (`.cvpack`). Each level names its algorithm in an
+//! `algorithm` field next to that algorithm's parameters:
+//!
+//! ```json
+//! {
+//!   "name": "CyberVault Basics",
+//!   "levels": [
+//!     { "id": "legacy", "title": "The license key", "reward": "FLAG{...}",
+//!       "algorithm": "mix", "seed": "0x12345678", "target": "0x85FD063D" }
+//!   ]
+//! }
+//! ```
+//!
+//! 32-bit parameters are written as `0x` hex strings.
+//!
+//! A reward is either plain text or `{ "sealed": "<hex>" }`, encrypted with
+//! [`seal`](crate::seal) under a secret computed from the answer (see
+//! [`Algorithm::answer_secret`]), so the pack never holds it in the clear.
+//! `seal_rewards` in `license_tools` seals the rewards of a pack given an
+//! accepted answer for each level.
+
+use serde::{Deserialize, Serialize};
+
+use crate::checksum::{check_legacy_key, conceal, key_hash};
+use crate::{LicenseError, seal, verify_key};
+use crate::vm;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengePack {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub levels: Vec<Level>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Level {
+    /// Stable identifier under which progress is saved.
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(flatten)]
+    pub algorithm: Algorithm,
+    /// Shown once the level is solved.
+    pub reward: Reward,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Reward {
+    /// Hex of the reward sealed under the level's [`Algorithm::answer_secret`].
+    Sealed { sealed: String },
+    Plain(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum Algorithm {
+    /// The `XXXX-XXXX-XXXX` key check: format rules, blocked patterns and the
+    /// [`mix`](crate::mix) state machine from `seed` to `target`.
+    Mix {
+        #[serde(with = "hex_u32")]
+        seed: u32,
+        #[serde(with = "hex_u32")]
+        target: u32,
+    },
+    /// The app's own license key check, [`verify_key`], so the level accepts
+    /// exactly the keys the activation window does. The level carries no
+    /// parameters, so a pack built into the app does not give away what the
+    /// check hides.
+    LicenseKey,
+    /// A CRC-32 with custom parameters over the answer's bytes.
+    Crc32 {
+        #[serde(with = "hex_u32")]
+        poly: u32,
+        #[serde(with = "hex_u32")]
+        init: u32,
+        /// Process bits LSB first and reflect the result, as in the common
+        /// CRC-32.
+        #[serde(default)]
+        reflect: bool,
+        #[serde(with = "hex_u32")]
+        xor_out: u32,
+        #[serde(with = "hex_u32")]
+        target: u32,
+    },
+    /// The answer, encrypted with RC4 under `key`, must equal `expected`
+    /// (hex).
+    Rc4 { key: String, expected: String },
+    /// A [`vm`] program (hex, whitespace ignored) that accepts the answer.
+    Vm { program: String },
+}
+
+impl Algorithm {
+    /// Name shown to players.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Algorithm::Mix { .. } => "Mix state machine",
+            Algorithm::LicenseKey => "License key check",
+            Algorithm::Crc32 { .. } => "CRC-32 variant",
+            Algorithm::Rc4 { .. } => "RC4 stream check",
+            Algorithm::Vm { .. } => "Bytecode VM",
+        }
+    }
+
+    pub fn check(&self, answer: &str) -> bool {
+        let bytes = answer.as_bytes();
+        match self {
+            Algorithm::Mix { seed, target } => check_legacy_key(answer, *seed, conceal(*target)).is_ok(),
+            Algorithm::LicenseKey => verify_key(answer).is_ok(),
+            Algorithm::Crc32 { poly, init, reflect, xor_out, target } => {
+                crc32(bytes, *poly, *init, *reflect) ^ xor_out == *target
+            }
+            Algorithm::Rc4 { key, expected } => {
+                decode_hex(expected).is_ok_and(|expected| rc4(key.as_bytes(), bytes) == expected)
+            }
+            Algorithm::Vm { program } => {
+                decode_hex(program).is_ok_and(|program| vm::run(&program, bytes) == Ok(true))
+            }
+        }
+    }
+
+    /// The secret a sealed reward is opened with: a value that every answer
+    /// the level accepts leads to. That is the hash state for `mix`, the CRC
+    /// for `crc32`, the answer itself for `rc4` and the stack the program
+    /// halts with for `vm`. The first three can also be worked out from the
+    /// level's parameters, so sealing keeps a reward out of `strings` but not
+    /// away from whoever understands the check. `license_key` levels accept
+    /// keys with different hashes and cannot seal their reward.
+    pub fn answer_secret(&self, answer: &str) -> Option<Vec<u8>> {
+        let bytes = answer.as_bytes();
+        match self {
+            Algorithm::Mix { seed, .. } => Some(key_hash(*seed, bytes).to_le_bytes().to_vec()),
+            Algorithm::LicenseKey => None,
+            Algorithm::Crc32 { poly, init, reflect, xor_out, .. } => {
+                Some((crc32(bytes, *poly, *init, *reflect) ^ xor_out).to_le_bytes().to_vec())
+            }
+            Algorithm::Rc4 { .. } => Some(bytes.to_vec()),
+            Algorithm::Vm { program } => {
+                let stack = vm::execute(&decode_hex(program).ok()?, bytes).ok()?;
+                Some(stack.iter().flat_map(|value| value.to_le_bytes()).collect())
+            }
+        }
+    }
+
+    /// Rejects parameters that could never be satisfied because they do not
+    /// decode.
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Algorithm::Rc4 { expected, .. } => decode_hex(expected).map(drop),
+            Algorithm::Vm { program } => decode_hex(program).map(drop),
+            Algorithm::Mix { .. } | Algorithm::LicenseKey | Algorithm::Crc32 { .. } => Ok(()),
+        }
+    }
+}
+
+impl ChallengePack {
+    pub fn parse(text: &str) -> Result<Self, LicenseError> {
+        let pack: Self = serde_json::from_str(text).map_err(|e| LicenseError::Malformed(e.to_string()))?;
+        if pack.levels.is_empty() {
+            return Err(LicenseError::Malformed("challenge pack has no levels".to_string()));
+        }
+        for (i, level) in pack.levels.iter().enumerate() {
+            if pack.levels[..i].iter().any(|other| other.id == level.id) {
+                return Err(LicenseError::Malformed(format!("duplicate level id '{}'", level.id)));
+            }
+            level
+                .validate()
+                .map_err(|e| LicenseError::Malformed(format!("level '{}': {}", level.id, e)))?;
+        }
+        Ok(pack)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("challenge pack serializes to JSON")
+    }
+}
+
+impl Level {
+    /// Whether `answer` solves the level. Surrounding whitespace is ignored.
+    pub fn check(&self, answer: &str) -> bool {
+        self.algorithm.check(answer.trim())
+    }
+
+    /// The reward for an `answer` that [`check`](Self::check) accepted.
+    /// Opening a sealed reward with any other answer yields garbage.
+    pub fn open_reward(&self, answer: &str) -> String {
+        match &self.reward {
+            Reward::Plain(text) => text.clone(),
+            Reward::Sealed { sealed } => {
+                let secret = self.algorithm.answer_secret(answer.trim()).unwrap_or_default();
+                let sealed = decode_hex(sealed).unwrap_or_default();
+                String::from_utf8_lossy(&seal(&secret, &sealed)).into_owned()
+            }
+        }
+    }
+
+    /// Replaces a plain reward with one sealed under `answer`, which must
+    /// solve the level.
+    pub fn seal_reward(&mut self, answer: &str) -> Result<(), LicenseError> {
+        let Reward::Plain(text) = &self.reward else {
+            return Err(LicenseError::Malformed(format!("level '{}': reward is already sealed", self.id)));
+        };
+        if !self.check(answer) {
+            return Err(LicenseError::Malformed(format!("level '{}': answer is not accepted", self.id)));
+        }
+        let secret = self
+            .algorithm
+            .answer_secret(answer.trim())
+            .ok_or_else(|| LicenseError::Malformed(format!("level '{}': reward cannot be sealed", self.id)))?;
+        self.reward = Reward::Sealed { sealed: hex::encode_upper(seal(&secret, text.as_bytes())) };
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.algorithm.validate()?;
+        match &self.reward {
+            Reward::Sealed { .. } if self.algorithm == Algorithm::LicenseKey => {
+                Err("license_key levels cannot seal their reward".to_string())
+            }
+            Reward::Sealed { sealed } => decode_hex(sealed).map(drop),
+            Reward::Plain(_) => Ok(()),
+        }
+    }
+}
+
+fn crc32(bytes: &[u8], poly: u32, init: u32, reflect: bool) -> u32 {
+    let mut crc = init;
+    for &byte in bytes {
+        if reflect {
+            let poly = poly.reverse_bits();
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { crc >> 1 ^ poly } else { crc >> 1 };
+            }
+        } else {
+            crc ^= (byte as u32) << 24;
+            for _ in 0..8 {
+                crc = if crc & 0x8000_0000 != 0 { crc << 1 ^ poly } else { crc << 1 };
+            }
+        }
+    }
+    crc
+}
+
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut s: Vec<u8> = (0..=255).collect();
+    if !key.is_empty() {
+        let mut j: u8 = 0;
+        for i in 0..256 {
+            j = j.wrapping_add(s[i]).wrapping_add(key[i % key.len()]);
+            s.swap(i, j as usize);
+        }
+    }
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|&byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(s[i as usize]);
+            s.swap(i as usize, j as usize);
+            byte ^ s[s[i as usize].wrapping_add(s[j as usize]) as usize]
+        })
+        .collect()
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    hex::decode(digits).map_err(|e| e.to_string())
+}
+
+/// `u32` parameters as `"0x1234ABCD"` strings.
+mod hex_u32 {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    pub fn serialize<S: Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{:08X}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let digits = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(&text);
+        u32::from_str_radix(digits, 16).map_err(|_| de::Error::custom(format!("'{}' is not a 32-bit hex value", text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILTIN_PACK: &str = include_str!("../../packs/cybervault.cvpack");
+
+    fn builtin_level(id: &str) -> Level {
+        let pack = ChallengePack::parse(BUILTIN_PACK).unwrap();
+        pack.levels.into_iter().find(|level| level.id == id).unwrap()
+    }
+
+    #[test]
+    fn crc32_matches_the_catalogued_variants() {
+        // Check values from the CRC catalogue, over "123456789".
+        let check = b"123456789";
+        assert_eq!(crc32(check, 0x04C1_1DB7, 0xFFFF_FFFF, true) ^ 0xFFFF_FFFF, 0xCBF4_3926);
+        assert_eq!(crc32(check, 0x04C1_1DB7, 0xFFFF_FFFF, false) ^ 0xFFFF_FFFF, 0xFC89_1918);
+        assert_eq!(crc32(check, 0x1EDC_6F41, 0xFFFF_FFFF, true) ^ 0xFFFF_FFFF, 0xE306_9283);
+        assert_eq!(crc32(b"", 0x04C1_1DB7, 0x1234_5678, true), 0x1234_5678);
+    }
+
+    #[test]
+    fn rc4_matches_the_published_vectors() {
+        assert_eq!(hex::encode(rc4(b"Key", b"Plaintext")), "bbf316e8d940af0ad3");
+        assert_eq!(hex::encode(rc4(b"Wiki", b"pedia")), "1021bf0420");
+        assert_eq!(rc4(b"Key", &rc4(b"Key", b"Plaintext")), b"Plaintext");
+    }
+
+    #[test]
+    fn builtin_levels_accept_their_answers() {
+        let crc = builtin_level("crc");
+        assert!(crc.check("4abvq88w") && crc.check(" 3cdpld9n\n"));
+        assert!(!crc.check("4abvq88x"));
+        let rc4 = builtin_level("rc4");
+        assert!(rc4.check("keystream reuse is fatal"));
+        assert!(!rc4.check("keystream reuse is fine!"));
+        let vm = builtin_level("vm");
+        assert!(vm.check("02udonz3") && vm.check("145donfq"));
+        assert!(!vm.check("02udonz4"));
+        assert!(builtin_level("legacy-key").check("4I1Q-ZKHI-HQ28"));
+    }
+
+    #[test]
+    fn sealed_rewards_open_with_any_accepted_answer() {
+        for (id, answers) in [("crc", ["4abvq88w", "3cdpld9n"]), ("vm", ["02udonz3", "145donfq"])] {
+            let level = builtin_level(id);
+            assert!(matches!(level.reward, Reward::Sealed { .. }), "{}", id);
+            let reward = level.open_reward(answers[0]);
+            assert!(reward.starts_with("FLAG{"), "{}: {}", id, reward);
+            assert_eq!(level.open_reward(answers[1]), reward, "{}", id);
+            assert_ne!(level.open_reward("wrong answer"), reward, "{}", id);
+        }
+        let rc4 = builtin_level("rc4");
+        assert!(rc4.open_reward("keystream reuse is fatal").starts_with("FLAG{"));
+    }
+
+    #[test]
+    fn seal_reward_round_trips() {
+        let mut level = Level {
+            id: "mix".to_string(),
+            title: "Mix".to_string(),
+            description: String::new(),
+            algorithm: Algorithm::Mix { seed: crate::KEY_SEED, target: crate::KEY_TARGET },
+            reward: Reward::Plain("FLAG{mixed}".to_string()),
+        };
+        assert!(level.clone().seal_reward("4I1Q-ZKHI-HQ29").is_err());
+        level.seal_reward("4I1Q-ZKHI-HQ28").unwrap();
+        assert!(matches!(level.reward, Reward::Sealed { .. }));
+        assert_eq!(level.open_reward("4I1Q-ZKHI-HQ28"), "FLAG{mixed}");
+        assert!(level.seal_reward("4I1Q-ZKHI-HQ28").is_err());
+
+        let mut key_level = Level { algorithm: Algorithm::LicenseKey, reward: Reward::Plain("x".into()), ..level };
+        assert!(key_level.seal_reward("4I1Q-ZKHI-HQ28").is_err());
+    }
+
+    #[test]
+    fn parse_rejects_bad_packs() {
+        assert!(ChallengePack::parse(r#"{ "name": "Empty", "levels": [] }"#).is_err());
+        let level = r#"{ "id": "a", "title": "A", "reward": "r", "algorithm": "rc4", "key": "k", "expected": "zz" }"#;
+        assert!(ChallengePack::parse(&format!(r#"{{ "name": "Bad hex", "levels": [{}] }}"#, level)).is_err());
+        let level = r#"{ "id": "a", "title": "A", "reward": "r", "algorithm": "license_key" }"#;
+        assert!(ChallengePack::parse(&format!(r#"{{ "name": "Dup", "levels": [{0}, {0}] }}"#, level)).is_err());
+        let pack = ChallengePack::parse(BUILTIN_PACK).unwrap();
+        assert_eq!(ChallengePack::parse(&pack.to_json()), Ok(pack));
+    }
+}
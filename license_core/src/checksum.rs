@@ -0,0 +1,225 @@
+use crate::{Edition, KeyFormat, License, LicenseError};
+
+const KEY_LEN: usize = 14;
+
+/// Initial state of the key hash.
+pub const KEY_SEED: u32 = 0x1234_5678;
+/// Final state a valid key must hash to.
+pub const KEY_TARGET: u32 = 0x85FD_063D;
+/// Applied after every [`mix`] round.
+const ROUND_XOR: u32 = 0x1111_1111;
+/// [`KEY_TARGET`] as the key check compares it.
+const CONCEALED_TARGET: u32 = conceal(KEY_TARGET);
+#[cfg(feature = "medium")]
+const CONCEAL_MUL: u32 = 0x2545_F491;
+#[cfg(feature = "medium")]
+const CONCEAL_XOR: u32 = 0x5BD1_E995;
+
+/// Validates a key in the `XXXX-XXXX-XXXX` checksum format.
+pub fn verify_legacy_key(input: &str) -> Result<License, LicenseError> {
+    check_legacy_key(input, KEY_SEED, CONCEALED_TARGET)?;
+    Ok(License::Key { key: input.to_string(), edition: Edition::Enterprise })
+}
+
+/// The final state in the form the key check compares it. Medium and hard
+/// builds scramble it, so the target never appears in the binary as such.
+#[cfg(not(feature = "medium"))]
+pub(crate) const fn conceal(x: u32) -> u32 {
+    x
+}
+
+#[cfg(feature = "medium")]
+pub(crate) const fn conceal(x: u32) -> u32 {
+    x.wrapping_mul(CONCEAL_MUL) ^ CONCEAL_XOR
+}
+
+/// The legacy key check with a different starting state and target, as used
+/// by challenge pack levels. `target` is [`conceal`]ed.
+pub(crate) fn check_legacy_key(input: &str, seed: u32, target: u32) -> Result<(), LicenseError> {
+    let b = input.as_bytes();
+    if b.len() != KEY_LEN {
+        return Err(LicenseError::WrongLength { expected: KEY_LEN, found: b.len() });
+    }
+    if b[4] != b'-' || b[9] != b'-' {
+        return Err(LicenseError::BadSeparators { pattern: KeyFormat::Legacy.pattern() });
+    }
+
+    if let Some(pattern) = blocked_pattern(input) {
+        return Err(LicenseError::BlockedPattern(pattern));
+    }
+
+    if run_key_hash(b, seed, target) {
+        Ok(())
+    } else {
+        Err(LicenseError::ChecksumMismatch)
+    }
+}
+
+#[cfg(not(feature = "hard"))]
+fn run_key_hash(b: &[u8], seed: u32, target: u32) -> bool {
+    let mut x: u32 = seed;
+
+    let mut i: usize = 0;
+    let mut state: u8 = 0;
+    while i < b.len() {
+        match state {
+            0 => { x = mix(x, b[i]); state = 1; }
+            1 => { x ^= ROUND_XOR; state = 2; }
+            _ => { i += 1; state = 0; }
+        }
+    }
+    #[cfg(not(feature = "medium"))]
+    tracing::debug!("DEBUG x = 0x{:08X}", x);
+    conceal(x) == target
+}
+
+/// Hard builds run the hash and the comparison as [`vm`](crate::vm)
+/// bytecode, with [`mix`] spelled out in plain arithmetic.
+#[cfg(feature = "hard")]
+fn run_key_hash(b: &[u8], seed: u32, target: u32) -> bool {
+    let mut program = Vec::with_capacity(71);
+    let push = |program: &mut Vec<u8>, value: u32| {
+        program.push(0x01);
+        program.extend(value.to_le_bytes());
+    };
+    push(&mut program, seed); // x
+    push(&mut program, 0); // x i
+    // 10: loop until i == len
+    program.extend([0x03, 0x06, 0x0F, 0x12, 19, 0, 0x11, 51, 0]);
+    // 19: x = mix(x, input[i]) ^ ROUND_XOR; i += 1
+    program.extend([0x03, 0x07, 0x05, 0x0B]);
+    push(&mut program, 5);
+    program.push(0x0E);
+    push(&mut program, 0x9E37_79B9);
+    program.push(0x08);
+    push(&mut program, 0xA5A5_5A5A ^ ROUND_XOR);
+    program.extend([0x0B, 0x04]);
+    push(&mut program, 1);
+    program.extend([0x08, 0x11, 10, 0]);
+    // 51: conceal(x) == target
+    program.push(0x02);
+    push(&mut program, CONCEAL_MUL);
+    program.push(0x0A);
+    push(&mut program, CONCEAL_XOR);
+    program.push(0x0B);
+    push(&mut program, target);
+    program.extend([0x0F, 0x00]);
+    crate::vm::run(&program, b) == Ok(true)
+}
+
+/// One round of the key hash: folds `byte` into the running state `x`.
+pub fn mix(mut x: u32, byte: u8) -> u32 {
+    x ^= byte as u32;
+    x = x.rotate_left(5).wrapping_add(0x9E37_79B9);
+    x ^ 0xA5A5_5A5A
+}
+
+/// Inverse of [`mix`]: the state before `byte` was folded in.
+pub fn unmix(x: u32, byte: u8) -> u32 {
+    let x = (x ^ 0xA5A5_5A5A).wrapping_sub(0x9E37_79B9).rotate_right(5);
+    x ^ byte as u32
+}
+
+/// Runs the key hash over `bytes` starting from `seed`. `verify_key` accepts
+/// a key when this yields [`KEY_TARGET`] from [`KEY_SEED`].
+pub fn key_hash(seed: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(seed, |x, &b| mix(x, b) ^ ROUND_XOR)
+}
+
+/// Reverses [`key_hash`]: the state that hashes `bytes` into `x`.
+pub fn key_unhash(x: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().rev().fold(x, |x, &b| unmix(x ^ ROUND_XOR, b))
+}
+
+/// Substrings that are rejected before the checksum is computed.
+pub fn blocked_pattern(key: &str) -> Option<&'static str> {
+    if key.starts_with("TEST") {
+        Some("TEST")
+    } else if key.contains("AAAA") {
+        Some("AAAA")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_KEY: &str = "4I1Q-ZKHI-HQ28";
+
+    #[test]
+    fn unmix_inverts_mix() {
+        for x in [0, 1, KEY_SEED, KEY_TARGET, u32::MAX] {
+            for byte in [0, b'-', b'Z', 0xFF] {
+                assert_eq!(unmix(mix(x, byte), byte), x);
+                assert_eq!(mix(unmix(x, byte), byte), x);
+            }
+        }
+    }
+
+    #[test]
+    fn key_unhash_inverts_key_hash() {
+        for bytes in [&b""[..], b"A", VALID_KEY.as_bytes(), &[0xFF; 32]] {
+            assert_eq!(key_unhash(key_hash(KEY_SEED, bytes), bytes), KEY_SEED);
+            assert_eq!(key_hash(key_unhash(KEY_TARGET, bytes), bytes), KEY_TARGET);
+        }
+        assert_eq!(key_hash(KEY_SEED, VALID_KEY.as_bytes()), KEY_TARGET);
+    }
+
+    #[test]
+    fn accepts_a_valid_key() {
+        assert_eq!(
+            verify_legacy_key(VALID_KEY),
+            Ok(License::Key { key: VALID_KEY.to_string(), edition: Edition::Enterprise })
+        );
+        assert_eq!(verify_legacy_key("4I1Q-ZKHI-HQ29"), Err(LicenseError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn rejects_blocked_patterns_before_hashing() {
+        assert_eq!(blocked_pattern("TEST-1234-5678"), Some("TEST"));
+        assert_eq!(blocked_pattern("12AB-AAAA-5678"), Some("AAAA"));
+        assert_eq!(blocked_pattern("1TES-TAAA-5678"), None);
+        assert_eq!(blocked_pattern(VALID_KEY), None);
+        assert_eq!(verify_legacy_key("TEST-1234-5678"), Err(LicenseError::BlockedPattern("TEST")));
+        assert_eq!(verify_legacy_key("1234-AAAA-5678"), Err(LicenseError::BlockedPattern("AAAA")));
+        // A key made to hash to the target is still refused.
+        let forged = *b"TEST-ZKHI-HQ28";
+        let seed = key_unhash(KEY_TARGET, &forged);
+        assert_eq!(
+            check_legacy_key("TEST-ZKHI-HQ28", seed, conceal(KEY_TARGET)),
+            Err(LicenseError::BlockedPattern("TEST"))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_keys() {
+        assert_eq!(verify_legacy_key("4I1Q-ZKHI"), Err(LicenseError::WrongLength { expected: KEY_LEN, found: 9 }));
+        assert_eq!(
+            verify_legacy_key("4I1Q_ZKHI_HQ28"),
+            Err(LicenseError::BadSeparators { pattern: KeyFormat::Legacy.pattern() })
+        );
+    }
+
+    /// Whichever tier is built, the key check agrees with [`key_hash`]; run
+    /// with `--features hard` to cover the bytecode path.
+    #[test]
+    fn tiered_check_matches_key_hash() {
+        let mut rng = 0x2545_F491u32;
+        for _ in 0..64 {
+            let input: Vec<u8> = (0..14)
+                .map(|_| {
+                    rng ^= rng << 13;
+                    rng ^= rng >> 17;
+                    rng ^= rng << 5;
+                    rng as u8
+                })
+                .collect();
+            let seed = rng;
+            let target = conceal(key_hash(seed, &input));
+            assert!(run_key_hash(&input, seed, target));
+            assert!(!run_key_hash(&input, seed, target ^ 1));
+        }
+    }
+}
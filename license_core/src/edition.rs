@@ -0,0 +1,35 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Product tier a license was sold for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Edition {
+    Trial,
+    Pro,
+    Enterprise,
+}
+
+impl FromStr for Edition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "trial" => Ok(Edition::Trial),
+            "pro" => Ok(Edition::Pro),
+            "enterprise" => Ok(Edition::Enterprise),
+            _ => Err(format!("unknown edition \"{}\" (expected trial, pro or enterprise)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Edition::Trial => "Trial",
+            Edition::Pro => "Pro",
+            Edition::Enterprise => "Enterprise",
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Edition;
+
+/// A licensable part of the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Feature {
+    Encryption,
+    Passwords,
+    Vault,
+    ActivityLog,
+    SecurityScan,
+    BackupRestore,
+    Analytics,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 7] = [
+        Feature::Encryption,
+        Feature::Passwords,
+        Feature::Vault,
+        Feature::ActivityLog,
+        Feature::SecurityScan,
+        Feature::BackupRestore,
+        Feature::Analytics,
+    ];
+
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+impl FromStr for Feature {
+    type Err = ();
+
+    /// Parses the identifiers used in the `features` list of license claims.
+    fn from_str(s: &str) -> Result<Self, ()> {
+        Feature::ALL
+            .into_iter()
+            .find(|f| format!("{:?}", f) == s)
+            .ok_or(())
+    }
+}
+
+/// The set of features a license grants, and whether they may still change
+/// data or only show it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Entitlements {
+    features: u16,
+    read_only: bool,
+}
+
+impl Entitlements {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(self, feature: Feature) -> bool {
+        self.features & feature.bit() != 0
+    }
+
+    /// Whether `feature` may create, change or delete data, not just show it.
+    pub fn can_modify(self, feature: Feature) -> bool {
+        self.contains(feature) && !self.read_only
+    }
+
+    pub fn insert(&mut self, feature: Feature) {
+        self.features |= feature.bit();
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.features == 0
+    }
+
+    /// The same features, limited to viewing and exporting.
+    pub fn read_only(self) -> Self {
+        Self { read_only: true, ..self }
+    }
+
+    pub fn is_read_only(self) -> bool {
+        self.read_only
+    }
+
+    /// Features in this set, in [`Feature::ALL`] order.
+    pub fn iter(self) -> impl Iterator<Item = Feature> {
+        Feature::ALL.into_iter().filter(move |f| self.contains(*f))
+    }
+}
+
+impl FromIterator<Feature> for Entitlements {
+    fn from_iter<I: IntoIterator<Item = Feature>>(iter: I) -> Self {
+        let mut set = Entitlements::none();
+        for feature in iter {
+            set.insert(feature);
+        }
+        set
+    }
+}
+
+impl Edition {
+    /// Features every license of this edition includes.
+    pub fn entitlements(self) -> Entitlements {
+        use Feature::*;
+        match self {
+            Edition::Trial => [Encryption, Vault].into_iter().collect(),
+            Edition::Pro => [Encryption, Passwords, Vault].into_iter().collect(),
+            Edition::Enterprise => Feature::ALL.into_iter().collect(),
+        }
+    }
+}
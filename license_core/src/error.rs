@@ -0,0 +1,37 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+
+/// The reason a license key or license file was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LicenseError {
+    #[error("License key must be {expected} characters including dashes (got {found})")]
+    WrongLength { expected: usize, found: usize },
+    #[error("License key must use the {pattern} format")]
+    BadSeparators { pattern: &'static str },
+    #[error("License key contains '{0}', which is not used in license keys")]
+    InvalidCharacter(char),
+    #[error("License key version {0} is not supported")]
+    UnsupportedVersion(u8),
+    #[error("License key contains a blocked pattern (\"{0}\")")]
+    BlockedPattern(&'static str),
+    #[error("Group {group} of the license key is mistyped")]
+    GroupMismatch { group: usize },
+    #[error("License key checksum does not match")]
+    ChecksumMismatch,
+    #[error("License file is malformed: {0}")]
+    Malformed(String),
+    #[error("License file signature is invalid")]
+    BadSignature,
+    #[error("This build has no vendor key to check signatures against")]
+    NoVendorKey,
+    #[error("License is not valid until {from}")]
+    NotYetValid { from: NaiveDate },
+    #[error("License expired on {on}")]
+    Expired { on: NaiveDate },
+    #[error("License is bound to a different machine")]
+    WrongMachine,
+    #[error("License has been revoked")]
+    Revoked,
+    #[error("Code is malformed: {0}")]
+    BadCode(&'static str),
+}
@@ -0,0 +1,174 @@
+//! Per-player flags for CTF events.
+//!
+//! An event ships a [`ChallengeConfig`] next to the app. Its secret is sealed
+//! like the challenge flag (see [`seal_flag`]), so it only opens with a valid
+//! key. Each player's flag is an HMAC of their player ID under that secret:
+//! a flag passed to someone else fails verification under their ID.
+//!
+//! The config also carries the event's [`Scoring`]: hints players can reveal
+//! one at a time, each lowering their score. The number revealed goes into
+//! the flag's HMAC too, so the organizer can tell the score from the flag.
+//! Without a config the app uses [`Scoring::default`].
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::fingerprint::hex_encode;
+use crate::{KEY_TARGET, LicenseError, seal_flag, unseal_flag};
+
+/// Hex digits of the HMAC that make up a flag.
+const FLAG_DIGITS: usize = 32;
+const DEFAULT_MAX_SCORE: u32 = 1000;
+const DEFAULT_PENALTY: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeConfig {
+    pub event: String,
+    /// Event secret sealed with [`seal_flag`] for [`KEY_TARGET`], hex encoded.
+    pub secret: String,
+    #[serde(flatten)]
+    pub scoring: Scoring,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scoring {
+    /// Score for solving without hints.
+    #[serde(default = "default_max_score")]
+    pub max_score: u32,
+    /// Revealed in order.
+    #[serde(default = "default_hints")]
+    pub hints: Vec<Hint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hint {
+    pub text: String,
+    /// Taken off the score once the hint is revealed.
+    #[serde(default = "default_penalty")]
+    pub penalty: u32,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self { max_score: DEFAULT_MAX_SCORE, hints: default_hints() }
+    }
+}
+
+impl Scoring {
+    /// The score after the first `revealed` hints were shown.
+    pub fn score(&self, revealed: usize) -> u32 {
+        let penalty: u32 = self.hints.iter().take(revealed).map(|hint| hint.penalty).sum();
+        self.max_score.saturating_sub(penalty)
+    }
+}
+
+fn default_max_score() -> u32 {
+    DEFAULT_MAX_SCORE
+}
+
+fn default_penalty() -> u32 {
+    DEFAULT_PENALTY
+}
+
+/// Hints for the built-in license key challenge.
+fn default_hints() -> Vec<Hint> {
+    [
+        ("Look at the state machine in the key check.", 100),
+        ("State 1 XORs a constant into the hash after every character.", 200),
+        ("mix is invertible for a known byte, so the hash can be run backwards from the target.", 300),
+    ]
+    .into_iter()
+    .map(|(text, penalty)| Hint { text: text.to_string(), penalty })
+    .collect()
+}
+
+impl ChallengeConfig {
+    pub fn new(event: &str, secret: &str) -> Self {
+        let sealed = seal_flag(KEY_TARGET, secret.as_bytes());
+        Self { event: event.to_string(), secret: hex_encode(&sealed), scoring: Scoring::default() }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, LicenseError> {
+        let config: Self = serde_json::from_str(text).map_err(|e| LicenseError::Malformed(e.to_string()))?;
+        if config.sealed_secret().is_none() {
+            return Err(LicenseError::Malformed("challenge config secret is not hex".to_string()));
+        }
+        Ok(config)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("challenge config serializes to JSON")
+    }
+
+    /// Opens the event secret with the legacy key `key`.
+    pub fn open_secret(&self, key: &str) -> String {
+        unseal_flag(key, &self.sealed_secret().unwrap_or_default())
+    }
+
+    fn sealed_secret(&self) -> Option<Vec<u8>> {
+        hex::decode(self.secret.trim()).ok()
+    }
+}
+
+/// Player IDs are compared without surrounding whitespace or case.
+pub fn normalize_player_id(player_id: &str) -> String {
+    player_id.trim().to_lowercase()
+}
+
+/// The flag for `player_id` at `event` after `hints` hints were revealed.
+pub fn player_flag(secret: &str, event: &str, player_id: &str, hints: usize) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(event.as_bytes());
+    mac.update(b"\0");
+    mac.update(normalize_player_id(player_id).as_bytes());
+    mac.update(b"\0");
+    mac.update(hints.to_string().as_bytes());
+    let digest = hex_encode(&mac.finalize().into_bytes());
+    format!("FLAG{{{}}}", &digest[..FLAG_DIGITS])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "event-secret";
+
+    #[test]
+    fn player_flag_verifies_only_for_its_player_and_hints() {
+        let flag = player_flag(SECRET, "ctf-2025", "alice", 1);
+        assert!(flag.starts_with("FLAG{") && flag.ends_with('}'));
+        assert_eq!(flag.len(), FLAG_DIGITS + 6);
+        assert_eq!(player_flag(SECRET, "ctf-2025", " Alice\n", 1), flag);
+
+        assert_ne!(player_flag(SECRET, "ctf-2025", "bob", 1), flag);
+        assert_ne!(player_flag(SECRET, "ctf-2025", "alice", 0), flag);
+        assert_ne!(player_flag(SECRET, "ctf-2025", "alice", 2), flag);
+        assert_ne!(player_flag(SECRET, "ctf-2026", "alice", 1), flag);
+        assert_ne!(player_flag("other-secret", "ctf-2025", "alice", 1), flag);
+    }
+
+    #[test]
+    fn player_and_event_cannot_be_shifted_into_each_other() {
+        assert_ne!(player_flag(SECRET, "ctf", "1alice", 0), player_flag(SECRET, "ctf1", "alice", 0));
+        assert_ne!(player_flag(SECRET, "ctf", "alice1", 0), player_flag(SECRET, "ctf", "alice", 10));
+    }
+
+    #[test]
+    fn secret_opens_with_the_valid_key_only() {
+        let config = ChallengeConfig::parse(&ChallengeConfig::new("ctf-2025", SECRET).to_json()).unwrap();
+        assert_eq!(config.open_secret("4I1Q-ZKHI-HQ28"), SECRET);
+        assert_ne!(config.open_secret("ABCD-EFGH-JKLM"), SECRET);
+    }
+
+    #[test]
+    fn score_drops_with_each_hint() {
+        let scoring = Scoring::default();
+        assert_eq!(scoring.score(0), 1000);
+        assert_eq!(scoring.score(1), 900);
+        assert_eq!(scoring.score(3), 400);
+        assert_eq!(scoring.score(usize::MAX), 400);
+        let harsh = Scoring { max_score: 100, hints: vec![Hint { text: String::new(), penalty: 500 }] };
+        assert_eq!(harsh.score(1), 0);
+    }
+}
@@ -0,0 +1,74 @@
+use std::fs;
+
+use sha2::{Digest, Sha256};
+
+/// Identifiers that are stable for the lifetime of a machine installation.
+///
+/// On Linux these come from `/etc/machine-id`, the kernel hostname and the
+/// MAC addresses of physical network interfaces. Other platforms only have
+/// the hostname.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachineFingerprint {
+    pub machine_id: Option<String>,
+    pub hostname: Option<String>,
+    pub mac_addresses: Vec<String>,
+}
+
+impl MachineFingerprint {
+    pub fn collect() -> Self {
+        Self {
+            machine_id: read_trimmed("/etc/machine-id")
+                .or_else(|| read_trimmed("/var/lib/dbus/machine-id")),
+            hostname: read_trimmed("/proc/sys/kernel/hostname")
+                .or_else(|| std::env::var("COMPUTERNAME").ok())
+                .or_else(|| std::env::var("HOSTNAME").ok()),
+            mac_addresses: mac_addresses(),
+        }
+    }
+
+    /// SHA-256 over a canonical encoding of every identifier.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(b"cybervault-fingerprint-v1\n");
+        hasher.update(self.machine_id.as_deref().unwrap_or("").as_bytes());
+        hasher.update(b"\n");
+        hasher.update(self.hostname.as_deref().unwrap_or("").as_bytes());
+        hasher.update(b"\n");
+        hasher.update(self.mac_addresses.join(",").as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Short hex identifier for binding licenses to this machine: the first
+    /// [`MACHINE_ID_LEN`] bytes of [`digest`](Self::digest).
+    pub fn machine_id(&self) -> String {
+        hex_encode(&self.digest()[..MACHINE_ID_LEN])
+    }
+}
+
+pub const MACHINE_ID_LEN: usize = 12;
+
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn read_trimmed(path: &str) -> Option<String> {
+    let value = fs::read_to_string(path).ok()?.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Sorted MAC addresses of interfaces backed by a physical device. Virtual
+/// interfaces (bridges, VPNs, containers) come and go, so they are skipped.
+fn mac_addresses() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/sys/class/net") else {
+        return Vec::new();
+    };
+    let mut macs: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().join("device").exists())
+        .filter_map(|entry| read_trimmed(&format!("{}/address", entry.path().display())))
+        .filter(|mac| mac != "00:00:00:00:00:00")
+        .collect();
+    macs.sort();
+    macs.dedup();
+    macs
+}
@@ -0,0 +1,64 @@
+//! The challenge flag, sealed at build time so that only the state a valid
+//! legacy key hashes to can open it.
+//!
+//! Every valid key hashes to the same 32-bit state, so that state is all the
+//! seal can be keyed with. It is stretched with Argon2id first, which makes
+//! each guess as slow as opening the flag and a search of all 2^32 states a
+//! matter of years rather than seconds. That only helps while the state is
+//! hidden: easy builds compare against it in plain sight, and whoever reads
+//! it out of any build opens the flag without a key.
+//!
+//! The flag is XORed with a keystream of SHA-256 blocks over a domain tag,
+//! the stretched key and a block counter. Opening it with the state of a key
+//! that did not really pass [`verify_legacy_key`](crate::verify_legacy_key)
+//! yields garbage rather than an error.
+//!
+//! [`seal`] is the same cipher under an arbitrary secret, for challenge level
+//! rewards and the decoy flag.
+
+use argon2::{Argon2, Params};
+use sha2::{Digest, Sha256};
+
+use crate::checksum::{KEY_SEED, key_hash};
+
+const DOMAIN: &[u8] = b"cybervault-flag-v2";
+/// Argon2id memory cost in KiB and iterations, the `.cvault` defaults.
+const STRETCH_MEMORY: u32 = 19 * 1024;
+const STRETCH_ITERATIONS: u32 = 2;
+
+/// Encrypts `flag` for the hash state `state`, normally [`KEY_TARGET`](crate::KEY_TARGET).
+pub fn seal_flag(state: u32, flag: &[u8]) -> Vec<u8> {
+    seal(&state.to_le_bytes(), flag)
+}
+
+/// Decrypts a sealed flag with the hash state of `key`.
+pub fn unseal_flag(key: &str, sealed: &[u8]) -> String {
+    let state = key_hash(KEY_SEED, key.as_bytes());
+    String::from_utf8_lossy(&seal(&state.to_le_bytes(), sealed)).into_owned()
+}
+
+/// Encrypts `data` under `secret`. The cipher is its own inverse, so sealing
+/// the output again with the same secret opens it.
+pub fn seal(secret: &[u8], data: &[u8]) -> Vec<u8> {
+    let key = stretch(secret);
+    data.chunks(32)
+        .enumerate()
+        .flat_map(|(block, chunk)| {
+            let pad = Sha256::new()
+                .chain_update(DOMAIN)
+                .chain_update(key)
+                .chain_update((block as u32).to_le_bytes())
+                .finalize();
+            chunk.iter().zip(pad).map(|(byte, pad)| byte ^ pad).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn stretch(secret: &[u8]) -> [u8; 32] {
+    let params = Params::new(STRETCH_MEMORY, STRETCH_ITERATIONS, 1, Some(32)).expect("valid Argon2 parameters");
+    let mut key = [0u8; 32];
+    Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(secret, DOMAIN, &mut key)
+        .expect("Argon2 accepts a 32-byte output and the domain as salt");
+    key
+}
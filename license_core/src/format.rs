@@ -0,0 +1,188 @@
+//! Registry of product key formats. Keys are routed to the validator of the
+//! format their shape matches.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{License, LicenseError, checksum, key_v2};
+
+/// Characters in a legacy key, not counting dashes.
+const LEGACY_CHARS: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// `XXXX-XXXX-XXXX` keys checked by the legacy hash.
+    Legacy,
+    /// `XXXXXXX-XXXXXXX-XXXXXXX` base32 keys carrying a version, edition,
+    /// CRC and a check digit per group.
+    V2,
+}
+
+/// Result of checking one group of a key on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupCheck {
+    Incomplete,
+    Valid,
+    Invalid,
+}
+
+impl KeyFormat {
+    pub const ALL: [KeyFormat; 2] = [KeyFormat::Legacy, KeyFormat::V2];
+
+    /// Placeholder for an empty key field.
+    pub fn pattern(self) -> &'static str {
+        match self {
+            KeyFormat::Legacy => "XXXX-XXXX-XXXX",
+            KeyFormat::V2 => "XXXXXXX-XXXXXXX-XXXXXXX",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            KeyFormat::Legacy => "14 characters including dashes",
+            KeyFormat::V2 => "23 characters including dashes; digits 2-9 and letters except I and O",
+        }
+    }
+
+    /// The format `input` is shaped like. More characters than a legacy key
+    /// holds, or a first group longer than four when dashes are typed, means
+    /// V2; anything else, including a partly typed key, is taken for legacy.
+    pub fn detect(input: &str) -> KeyFormat {
+        let compact_len = compact(input).len();
+        let first_group = input.split_once('-').map(|(group, _)| compact(group).len());
+        if compact_len > LEGACY_CHARS || first_group.is_some_and(|len| len > 4) {
+            KeyFormat::V2
+        } else {
+            KeyFormat::Legacy
+        }
+    }
+
+    /// Uppercases `input`, drops whitespace and puts the dashes where this
+    /// format has them. Works on partial input, so it can run as you type.
+    pub fn normalize(self, input: &str) -> String {
+        let group_len = match self {
+            KeyFormat::Legacy => 4,
+            KeyFormat::V2 => key_v2::GROUP_LEN,
+        };
+        let chars: Vec<char> = compact(input).chars().collect();
+        chars
+            .chunks(group_len)
+            .map(|group| group.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Characters this format never uses because they are easily mistaken
+    /// for others (`O`/`0`, `I`/`1`). Legacy keys may contain anything.
+    pub fn is_ambiguous(self, c: char) -> bool {
+        self == KeyFormat::V2 && matches!(c.to_ascii_uppercase(), 'O' | '0' | 'I' | '1')
+    }
+
+    /// Checks each group of a normalized, possibly partial key. Empty for
+    /// formats without per-group check digits.
+    pub fn check_groups(self, input: &str) -> Vec<GroupCheck> {
+        match self {
+            KeyFormat::Legacy => Vec::new(),
+            KeyFormat::V2 => key_v2::check_groups(input),
+        }
+    }
+
+    /// Validates `input` exactly as given; see [`verify_key`] for the
+    /// normalizing entry point.
+    pub fn verify(self, input: &str) -> Result<License, LicenseError> {
+        match self {
+            KeyFormat::Legacy => checksum::verify_legacy_key(input),
+            KeyFormat::V2 => key_v2::verify_key(input),
+        }
+    }
+}
+
+impl fmt::Display for KeyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            KeyFormat::Legacy => "Legacy",
+            KeyFormat::V2 => "V2",
+        })
+    }
+}
+
+impl FromStr for KeyFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KeyFormat::ALL
+            .into_iter()
+            .find(|format| format.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown key format '{}' (expected legacy or v2)", s))
+    }
+}
+
+/// Normalizes a product key and validates it in whichever format it is
+/// written in.
+pub fn verify_key(input: &str) -> Result<License, LicenseError> {
+    let format = KeyFormat::detect(input);
+    let result = format.verify(&format.normalize(input));
+    tracing::debug!(%format, valid = result.is_ok(), "product key checked");
+    result
+}
+
+fn compact(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Edition, key_v2::encode_key};
+
+    #[test]
+    fn detects_the_format_from_its_shape() {
+        assert_eq!(KeyFormat::detect("4I1Q-ZKHI-HQ28"), KeyFormat::Legacy);
+        assert_eq!(KeyFormat::detect("4i1q"), KeyFormat::Legacy);
+        assert_eq!(KeyFormat::detect(""), KeyFormat::Legacy);
+        assert_eq!(KeyFormat::detect("ABCDEFG-"), KeyFormat::V2);
+        assert_eq!(KeyFormat::detect("abcdefghjkmnp"), KeyFormat::V2);
+        assert_eq!(KeyFormat::detect(&encode_key(Edition::Pro, 1)), KeyFormat::V2);
+    }
+
+    #[test]
+    fn normalizes_case_spaces_and_dashes() {
+        assert_eq!(KeyFormat::Legacy.normalize("4i1q zkhi hq28"), "4I1Q-ZKHI-HQ28");
+        assert_eq!(KeyFormat::Legacy.normalize("4I1QZKHIHQ28"), "4I1Q-ZKHI-HQ28");
+        assert_eq!(KeyFormat::Legacy.normalize(" 4i1q-zk"), "4I1Q-ZK");
+        assert_eq!(KeyFormat::V2.normalize("abcdefg hjkmnpq-rstuvwx"), "ABCDEFG-HJKMNPQ-RSTUVWX");
+        assert_eq!(KeyFormat::V2.normalize("abcdefgh"), "ABCDEFG-H");
+    }
+
+    #[test]
+    fn verifies_keys_however_they_are_typed() {
+        assert!(verify_key("4i1q zkhi hq28").is_ok());
+        assert!(verify_key("4I1QZKHIHQ28").is_ok());
+        let key = encode_key(Edition::Enterprise, 99);
+        let sloppy = key.to_lowercase().replace('-', " ");
+        assert_eq!(verify_key(&sloppy), Ok(License::Key { key, edition: Edition::Enterprise }));
+    }
+
+    #[test]
+    fn flags_ambiguous_characters_in_v2_only() {
+        for c in ['O', 'o', '0', 'I', 'i', '1'] {
+            assert!(KeyFormat::V2.is_ambiguous(c), "{}", c);
+            assert!(!KeyFormat::Legacy.is_ambiguous(c), "{}", c);
+        }
+        assert!(!KeyFormat::V2.is_ambiguous('Q'));
+        let key = encode_key(Edition::Pro, 5);
+        let mistyped = format!("O{}", &key[1..]);
+        assert_eq!(verify_key(&mistyped), Err(LicenseError::InvalidCharacter('O')));
+    }
+
+    #[test]
+    fn parses_format_names() {
+        assert_eq!("v2".parse(), Ok(KeyFormat::V2));
+        assert_eq!("LEGACY".parse(), Ok(KeyFormat::Legacy));
+        assert!("v3".parse::<KeyFormat>().is_err());
+    }
+}
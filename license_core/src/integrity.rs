@@ -0,0 +1,74 @@
+//! Integrity stamp for the `.text` section of a 64-bit little-endian ELF
+//! executable, as used by the app's optional anti-debugging layer.
+//!
+//! The app carries [`EMPTY_TEXT_STAMP`] as a static: [`TEXT_STAMP_MAGIC`]
+//! followed by 32 zero bytes. After linking, [`stamp_text_hash`] writes the
+//! SHA-256 of `.text` over the zeros. The stamp lives outside `.text`, so
+//! writing it does not change the hash it records.
+
+use sha2::{Digest, Sha256};
+
+pub const TEXT_STAMP_MAGIC: [u8; 16] = *b"cybervault-text\0";
+pub const TEXT_STAMP_LEN: usize = TEXT_STAMP_MAGIC.len() + 32;
+
+/// The stamp as linked, before [`stamp_text_hash`] fills in the hash.
+pub const EMPTY_TEXT_STAMP: [u8; TEXT_STAMP_LEN] = {
+    let mut stamp = [0u8; TEXT_STAMP_LEN];
+    let mut i = 0;
+    while i < TEXT_STAMP_MAGIC.len() {
+        stamp[i] = TEXT_STAMP_MAGIC[i];
+        i += 1;
+    }
+    stamp
+};
+
+/// SHA-256 of the `.text` section, or `None` if `elf` is not a 64-bit
+/// little-endian ELF file with one.
+pub fn text_section_hash(elf: &[u8]) -> Option<[u8; 32]> {
+    let (offset, size) = text_section(elf)?;
+    let text = elf.get(offset..offset.checked_add(size)?)?;
+    Some(Sha256::digest(text).into())
+}
+
+/// Writes the `.text` hash into the executable's stamp and returns it.
+/// Stamping again replaces the previous hash.
+pub fn stamp_text_hash(elf: &mut [u8]) -> Result<[u8; 32], String> {
+    let hash = text_section_hash(elf).ok_or("not a 64-bit little-endian ELF file with a .text section")?;
+    let mut stamps = elf
+        .windows(TEXT_STAMP_MAGIC.len())
+        .enumerate()
+        .filter(|(_, window)| *window == TEXT_STAMP_MAGIC)
+        .map(|(at, _)| at);
+    let at = match (stamps.next(), stamps.next()) {
+        (Some(at), None) if at + TEXT_STAMP_LEN <= elf.len() => at,
+        (None, _) => return Err("the executable has no text stamp; was it built with anti-debug-text?".to_string()),
+        _ => return Err("the executable has more than one text stamp".to_string()),
+    };
+    elf[at + TEXT_STAMP_MAGIC.len()..at + TEXT_STAMP_LEN].copy_from_slice(&hash);
+    Ok(hash)
+}
+
+/// File offset and size of `.text`, read from the section headers.
+fn text_section(elf: &[u8]) -> Option<(usize, usize)> {
+    if elf.get(..6)? != b"\x7fELF\x02\x01" {
+        return None;
+    }
+    let u16_at = |at: usize| Some(u16::from_le_bytes(elf.get(at..at + 2)?.try_into().ok()?) as usize);
+    let u32_at = |at: usize| Some(u32::from_le_bytes(elf.get(at..at + 4)?.try_into().ok()?) as usize);
+    let u64_at = |at: usize| usize::try_from(u64::from_le_bytes(elf.get(at..at + 8)?.try_into().ok()?)).ok();
+
+    let sections = u64_at(0x28)?;
+    let entry_size = u16_at(0x3A)?;
+    let count = u16_at(0x3C)?;
+    let header = |index: usize| sections.checked_add(index.checked_mul(entry_size)?);
+    let names = u64_at(header(u16_at(0x3E)?)? + 24)?;
+
+    (0..count).find_map(|index| {
+        let at = header(index)?;
+        let name = names.checked_add(u32_at(at)?)?;
+        if elf.get(name..name + 6)? != b".text\0" {
+            return None;
+        }
+        Some((u64_at(at + 24)?, u64_at(at + 32)?))
+    })
+}
@@ -0,0 +1,236 @@
+//! The `XXXXXXX-XXXXXXX-XXXXXXX` key format.
+//!
+//! A V2 key is 90 bits written as 18 base32 characters over an alphabet that
+//! leaves out `0`, `1`, `I` and `O`. From the most significant bit:
+//!
+//! | Bits | Field                                                  |
+//! |------|--------------------------------------------------------|
+//! | 4    | version, always 2                                      |
+//! | 2    | edition (0 Trial, 1 Pro, 2 Enterprise)                 |
+//! | 4    | reserved, zero                                         |
+//! | 32   | serial                                                 |
+//! | 32   | tag: [`key_hash`] of the header byte and the serial    |
+//! | 16   | CRC-16 of the 74 bits above, packed into 10 bytes      |
+//!
+//! The characters are split into three groups of six data characters plus a
+//! check digit (seven per group). The check digit is Luhn mod 32, offset by
+//! the group index, so that a typo can be pinned to its group before the
+//! whole key is decoded.
+
+use crc::{CRC_16_IBM_3740, Crc};
+
+use crate::checksum::{KEY_SEED, key_hash};
+use crate::{Edition, GroupCheck, KeyFormat, License, LicenseError};
+
+pub const ALPHABET: &[u8; 32] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+pub const GROUPS: usize = 3;
+/// Data characters per group, followed by one check digit.
+pub const DATA_LEN: usize = 6;
+pub const GROUP_LEN: usize = DATA_LEN + 1;
+pub const KEY_LEN: usize = GROUPS * GROUP_LEN + GROUPS - 1;
+
+const VERSION: u8 = 2;
+const CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+
+/// Builds the V2 key for `edition` and `serial`.
+pub fn encode_key(edition: Edition, serial: u32) -> String {
+    let body = (header(edition) as u128) << 64 | (serial as u128) << 32 | tag(edition, serial) as u128;
+    let bits = body << 16 | crc(body) as u128;
+    let values: Vec<u8> = (0..GROUPS * DATA_LEN).rev().map(|i| (bits >> (5 * i)) as u8 & 0x1F).collect();
+    values
+        .chunks(DATA_LEN)
+        .enumerate()
+        .map(|(group, data)| {
+            data.iter()
+                .chain([check_digit(data, group)].iter())
+                .map(|&v| ALPHABET[v as usize] as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Validates a key in the V2 format.
+pub fn verify_key(input: &str) -> Result<License, LicenseError> {
+    let b = input.as_bytes();
+    if b.len() != KEY_LEN {
+        return Err(LicenseError::WrongLength { expected: KEY_LEN, found: b.len() });
+    }
+    let groups: Vec<&str> = input.split('-').collect();
+    if groups.len() != GROUPS || groups.iter().any(|g| g.len() != GROUP_LEN) {
+        return Err(LicenseError::BadSeparators { pattern: KeyFormat::V2.pattern() });
+    }
+
+    let mut bits: u128 = 0;
+    for (index, group) in groups.iter().enumerate() {
+        let values = decode_group(group)?;
+        if check_digit(&values[..DATA_LEN], index) != values[DATA_LEN] {
+            return Err(LicenseError::GroupMismatch { group: index + 1 });
+        }
+        for &value in &values[..DATA_LEN] {
+            bits = bits << 5 | value as u128;
+        }
+    }
+
+    let body = bits >> 16;
+    if crc(body) as u128 != bits & 0xFFFF {
+        return Err(LicenseError::ChecksumMismatch);
+    }
+    let header = (body >> 64) as u16;
+    let version = (header >> 6) as u8;
+    if version != VERSION {
+        return Err(LicenseError::UnsupportedVersion(version));
+    }
+    let edition = match (header >> 4) & 0b11 {
+        0 => Edition::Trial,
+        1 => Edition::Pro,
+        2 => Edition::Enterprise,
+        _ => return Err(LicenseError::ChecksumMismatch),
+    };
+    let serial = (body >> 32) as u32;
+    if header & 0xF != 0 || body as u32 != tag(edition, serial) {
+        return Err(LicenseError::ChecksumMismatch);
+    }
+    Ok(License::Key { key: input.to_string(), edition })
+}
+
+/// Checks each group of a (possibly partial) normalized key on its own.
+pub fn check_groups(input: &str) -> Vec<GroupCheck> {
+    let mut groups: Vec<&str> = input.split('-').collect();
+    groups.resize(GROUPS, "");
+    groups
+        .iter()
+        .enumerate()
+        .map(|(index, group)| match decode_group(group) {
+            Err(_) => GroupCheck::Invalid,
+            Ok(values) if values.len() < GROUP_LEN => GroupCheck::Incomplete,
+            Ok(values) if values.len() == GROUP_LEN && check_digit(&values[..DATA_LEN], index) == values[DATA_LEN] => {
+                GroupCheck::Valid
+            }
+            Ok(_) => GroupCheck::Invalid,
+        })
+        .collect()
+}
+
+fn decode_group(group: &str) -> Result<Vec<u8>, LicenseError> {
+    group
+        .chars()
+        .map(|c| {
+            ALPHABET
+                .iter()
+                .position(|&a| a as char == c)
+                .map(|v| v as u8)
+                .ok_or(LicenseError::InvalidCharacter(c))
+        })
+        .collect()
+}
+
+/// Luhn mod 32 over `data`, offset by `group` so that groups cannot be
+/// swapped.
+fn check_digit(data: &[u8], group: usize) -> u8 {
+    let mut factor = 2;
+    let mut sum = 0;
+    for &value in data.iter().rev() {
+        let addend = factor * value as u32;
+        sum += addend / 32 + addend % 32;
+        factor = 3 - factor;
+    }
+    ((64 - sum % 32 + group as u32) % 32) as u8
+}
+
+/// Version, edition and reserved bits: the top 10 bits of the key.
+fn header(edition: Edition) -> u16 {
+    let edition = match edition {
+        Edition::Trial => 0,
+        Edition::Pro => 1,
+        Edition::Enterprise => 2,
+    };
+    (VERSION as u16) << 6 | edition << 4
+}
+
+fn tag(edition: Edition, serial: u32) -> u32 {
+    let mut bytes = [0u8; 5];
+    bytes[0] = (header(edition) >> 2) as u8;
+    bytes[1..].copy_from_slice(&serial.to_be_bytes());
+    key_hash(KEY_SEED, &bytes)
+}
+
+fn crc(body: u128) -> u16 {
+    CRC16.checksum(&body.to_be_bytes()[6..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDITIONS: [Edition; 3] = [Edition::Trial, Edition::Pro, Edition::Enterprise];
+
+    /// Replaces character `index` of `key` with the next alphabet character.
+    fn substitute(key: &str, index: usize) -> String {
+        let mut chars: Vec<char> = key.chars().collect();
+        let value = ALPHABET.iter().position(|&a| a as char == chars[index]).unwrap();
+        chars[index] = ALPHABET[(value + 1) % ALPHABET.len()] as char;
+        chars.into_iter().collect()
+    }
+
+    /// Recomputes each group's check digit so that only the CRC and tag are
+    /// left to catch a change.
+    fn recheck(key: &str) -> String {
+        key.split('-')
+            .enumerate()
+            .map(|(index, group)| {
+                let values = decode_group(group).unwrap();
+                let digit = check_digit(&values[..DATA_LEN], index);
+                format!("{}{}", &group[..DATA_LEN], ALPHABET[digit as usize] as char)
+            })
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    #[test]
+    fn round_trips_every_edition() {
+        for edition in EDITIONS {
+            for serial in [0, 1, 0xDEAD_BEEF, u32::MAX] {
+                let key = encode_key(edition, serial);
+                assert_eq!(key.len(), KEY_LEN);
+                assert_eq!(verify_key(&key), Ok(License::Key { key: key.clone(), edition }));
+                assert_eq!(KeyFormat::detect(&key), KeyFormat::V2);
+            }
+        }
+    }
+
+    #[test]
+    fn pins_a_typo_to_its_group() {
+        let key = encode_key(Edition::Pro, 4242);
+        for group in 0..GROUPS {
+            for offset in 0..GROUP_LEN {
+                let typo = substitute(&key, group * (GROUP_LEN + 1) + offset);
+                assert_eq!(verify_key(&typo), Err(LicenseError::GroupMismatch { group: group + 1 }), "{}", typo);
+                let checks = check_groups(&typo);
+                assert_eq!(checks[group], GroupCheck::Invalid);
+                assert_eq!(checks.iter().filter(|&&c| c == GroupCheck::Valid).count(), GROUPS - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_a_bad_crc() {
+        let key = encode_key(Edition::Enterprise, 7);
+        let last_data = 2 * (GROUP_LEN + 1) + DATA_LEN - 1;
+        let forged = recheck(&substitute(&key, last_data));
+        assert_eq!(check_groups(&forged), vec![GroupCheck::Valid; GROUPS]);
+        assert_eq!(verify_key(&forged), Err(LicenseError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let key = encode_key(Edition::Trial, 1);
+        assert_eq!(verify_key(&key[1..]), Err(LicenseError::WrongLength { expected: KEY_LEN, found: KEY_LEN - 1 }));
+        assert_eq!(
+            verify_key(&key.replace('-', "_")),
+            Err(LicenseError::BadSeparators { pattern: KeyFormat::V2.pattern() })
+        );
+        let with_zero = format!("0{}", &key[1..]);
+        assert_eq!(verify_key(&with_zero), Err(LicenseError::InvalidCharacter('0')));
+    }
+}
@@ -0,0 +1,80 @@
+//! License verification for CyberVault Pro.
+//!
+//! This crate holds the key validation logic used by the `license_gate` GUI so
+//! that other tools can check keys without pulling in the UI stack.
+
+mod challenge;
+mod checksum;
+mod edition;
+mod entitlement;
+mod error;
+mod event;
+mod fingerprint;
+mod flag;
+mod format;
+mod integrity;
+mod key_v2;
+mod offline;
+pub mod protocol;
+mod revocation;
+mod signed;
+mod standing;
+mod tier;
+pub mod vault;
+pub mod vm;
+
+pub use challenge::{Algorithm, ChallengePack, Level, Reward};
+pub use checksum::{KEY_SEED, KEY_TARGET, blocked_pattern, key_hash, key_unhash, mix, unmix, verify_legacy_key};
+pub use edition::Edition;
+pub use entitlement::{Entitlements, Feature};
+pub use error::LicenseError;
+pub use event::{ChallengeConfig, Hint, Scoring, normalize_player_id, player_flag};
+pub use fingerprint::{MACHINE_ID_LEN, MachineFingerprint};
+pub use flag::{seal, seal_flag, unseal_flag};
+pub use format::{GroupCheck, KeyFormat, verify_key};
+pub use integrity::{EMPTY_TEXT_STAMP, TEXT_STAMP_LEN, TEXT_STAMP_MAGIC, stamp_text_hash, text_section_hash};
+pub use key_v2::encode_key as encode_v2_key;
+pub use offline::{DeactivationReceipt, deactivation_code, parse_deactivation_code, parse_request_code, request_code};
+pub use revocation::{RevocationList, SignedRevocationList};
+pub use signed::{Claims, SignedLicense, VENDOR_PUBLIC_KEY, verify_license_file};
+pub use standing::{DEFAULT_GRACE_DAYS, Standing};
+pub use tier::Tier;
+
+/// A license that passed every validation check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum License {
+    /// A product key. Keys carry no owner information; legacy
+    /// `XXXX-XXXX-XXXX` keys always grant the Enterprise edition, V2 keys
+    /// encode theirs.
+    Key { key: String, edition: Edition },
+    /// A signed license file and the claims it carries.
+    Signed { claims: Claims, file: SignedLicense },
+}
+
+impl License {
+    pub fn edition(&self) -> Edition {
+        match self {
+            License::Key { edition, .. } => *edition,
+            License::Signed { claims, .. } => claims.edition,
+        }
+    }
+
+    /// Features granted by the edition plus any listed in the license claims.
+    /// Unknown feature names in the claims are ignored.
+    pub fn entitlements(&self) -> Entitlements {
+        let mut set = self.edition().entitlements();
+        if let Some(claims) = self.claims() {
+            for feature in claims.features.iter().filter_map(|f| f.parse().ok()) {
+                set.insert(feature);
+            }
+        }
+        set
+    }
+
+    pub fn claims(&self) -> Option<&Claims> {
+        match self {
+            License::Key { .. } => None,
+            License::Signed { claims, .. } => Some(claims),
+        }
+    }
+}
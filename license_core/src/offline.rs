@@ -0,0 +1,295 @@
+//! Request codes for offline activation and receipts for offline
+//! deactivation.
+//!
+//! An air-gapped machine shows a request code that identifies it. The vendor
+//! portal answers with a response code: a [`SignedLicense`](crate::SignedLicense)
+//! in its compact form whose claims are bound to that machine.
+//!
+//! A request code is 15 bytes, base32 encoded into six groups of four:
+//! a version byte, the [`MACHINE_ID_LEN`]-byte machine id and a CRC-16 of the
+//! preceding bytes to catch typos.
+//!
+//! When a license is removed from a machine that cannot reach the activation
+//! server, the app shows a deactivation receipt instead. It is laid out the
+//! same way, with its own version byte, and carries the first
+//! [`LICENSE_REF_LEN`] bytes of a SHA-256 identifying the license, the day of
+//! deactivation (days since 1970, big endian `u16`) and the first
+//! [`RECEIPT_MAC_LEN`] bytes of an HMAC-SHA256 over those fields keyed by the
+//! machine id. The machine id is not in the receipt: the vendor knows it from
+//! the request code or the online activation, so a receipt made up by anyone
+//! else fails [`DeactivationReceipt::is_from_machine`]. The machine's owner
+//! can still produce one without removing the license, so a receipt proves
+//! where it came from, not that the license is gone.
+
+use chrono::{Days, NaiveDate};
+use crc::{CRC_16_IBM_3740, Crc};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::fingerprint::hex_encode;
+use crate::{KeyFormat, License, LicenseError, MACHINE_ID_LEN, MachineFingerprint};
+
+const REQUEST_VERSION: u8 = 1;
+const REQUEST_LEN: usize = 1 + MACHINE_ID_LEN + 2;
+const RECEIPT_VERSION: u8 = 3;
+const LICENSE_REF_LEN: usize = 8;
+const RECEIPT_MAC_LEN: usize = 8;
+const RECEIPT_BODY_LEN: usize = 1 + LICENSE_REF_LEN + 2;
+const RECEIPT_LEN: usize = RECEIPT_BODY_LEN + RECEIPT_MAC_LEN + 2;
+const CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+
+/// Errors for a code that is not base32, has the wrong length, an unknown
+/// version or a bad CRC.
+type CodeErrors = [&'static str; 4];
+const REQUEST_ERRORS: CodeErrors = [
+    "request code is not base32",
+    "request code has the wrong length",
+    "unsupported request code version",
+    "request code checksum mismatch",
+];
+const RECEIPT_ERRORS: CodeErrors = [
+    "receipt is not base32",
+    "receipt has the wrong length",
+    "unsupported receipt version",
+    "receipt checksum mismatch",
+];
+
+/// The request code for `machine`, e.g. `AEXM-2QZK-...`.
+pub fn request_code(machine: &MachineFingerprint) -> String {
+    let mut bytes = Vec::with_capacity(REQUEST_LEN);
+    bytes.push(REQUEST_VERSION);
+    bytes.extend_from_slice(&machine.digest()[..MACHINE_ID_LEN]);
+    encode(bytes)
+}
+
+/// Decodes a request code to the machine id it was generated for. Case,
+/// dashes and whitespace are ignored.
+pub fn parse_request_code(code: &str) -> Result<String, LicenseError> {
+    let body = decode(code, REQUEST_VERSION, REQUEST_LEN, REQUEST_ERRORS)?;
+    Ok(hex_encode(&body[1..]))
+}
+
+/// What an offline deactivation receipt says was removed, and when.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeactivationReceipt {
+    pub date: NaiveDate,
+    license: [u8; LICENSE_REF_LEN],
+    body: [u8; RECEIPT_BODY_LEN],
+    mac: [u8; RECEIPT_MAC_LEN],
+}
+
+impl DeactivationReceipt {
+    /// Whether the receipt was made on the machine with
+    /// [`MachineFingerprint::machine_id`] `machine_id`, as recorded when the
+    /// license was activated there.
+    pub fn is_from_machine(&self, machine_id: &str) -> bool {
+        hex::decode(machine_id.trim())
+            .is_ok_and(|machine| receipt_mac(&machine, &self.body).verify_truncated_left(&self.mac).is_ok())
+    }
+
+    /// Whether the receipt is for the product key `key`, however it is typed.
+    pub fn is_for_key(&self, key: &str) -> bool {
+        self.license == key_ref(key)
+    }
+
+    pub fn is_for_license_id(&self, license_id: &str) -> bool {
+        self.license == license_id_ref(license_id)
+    }
+}
+
+/// The receipt shown after `license` was removed from `machine` on `date`.
+pub fn deactivation_code(machine: &MachineFingerprint, license: &License, date: NaiveDate) -> String {
+    let license = match license {
+        License::Key { key, .. } => key_ref(key),
+        License::Signed { claims, .. } => license_id_ref(&claims.license_id),
+    };
+    let days = (date - epoch()).num_days().clamp(0, u16::MAX as i64) as u16;
+    let mut bytes = Vec::with_capacity(RECEIPT_LEN);
+    bytes.push(RECEIPT_VERSION);
+    bytes.extend_from_slice(&license);
+    bytes.extend_from_slice(&days.to_be_bytes());
+    let mac = receipt_mac(&machine.digest()[..MACHINE_ID_LEN], &bytes).finalize().into_bytes();
+    bytes.extend_from_slice(&mac[..RECEIPT_MAC_LEN]);
+    encode(bytes)
+}
+
+/// Decodes a deactivation receipt. Case, dashes and whitespace are ignored.
+pub fn parse_deactivation_code(code: &str) -> Result<DeactivationReceipt, LicenseError> {
+    let bytes = decode(code, RECEIPT_VERSION, RECEIPT_LEN, RECEIPT_ERRORS)?;
+    let (body, mac) = bytes.split_at(RECEIPT_BODY_LEN);
+    let (license, days) = body[1..].split_at(LICENSE_REF_LEN);
+    let days = u16::from_be_bytes([days[0], days[1]]);
+    Ok(DeactivationReceipt {
+        date: epoch() + Days::new(days as u64),
+        license: license.try_into().expect("slice has LICENSE_REF_LEN bytes"),
+        body: body.try_into().expect("slice has RECEIPT_BODY_LEN bytes"),
+        mac: mac.try_into().expect("slice has RECEIPT_MAC_LEN bytes"),
+    })
+}
+
+/// HMAC over the receipt fields, keyed by the raw machine id.
+fn receipt_mac(machine: &[u8], body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(machine).expect("HMAC takes keys of any length");
+    mac.update(b"cybervault-receipt-v3\0");
+    mac.update(body);
+    mac
+}
+
+fn key_ref(key: &str) -> [u8; LICENSE_REF_LEN] {
+    let key = KeyFormat::detect(key).normalize(key);
+    license_ref(&key)
+}
+
+fn license_id_ref(license_id: &str) -> [u8; LICENSE_REF_LEN] {
+    license_ref(license_id)
+}
+
+fn license_ref(text: &str) -> [u8; LICENSE_REF_LEN] {
+    Sha256::digest(text.as_bytes())[..LICENSE_REF_LEN]
+        .try_into()
+        .expect("digest is longer than LICENSE_REF_LEN")
+}
+
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid date")
+}
+
+/// Appends the CRC and writes `bytes` as dash-separated groups of four.
+fn encode(mut bytes: Vec<u8>) -> String {
+    bytes.extend_from_slice(&CRC16.checksum(&bytes).to_be_bytes());
+    let encoded = BASE32_NOPAD.encode(&bytes);
+    encoded
+        .as_bytes()
+        .chunks(4)
+        .map(|group| std::str::from_utf8(group).expect("base32 is ASCII"))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Checks length, version and CRC of a code and returns it without the CRC.
+fn decode(code: &str, version: u8, len: usize, errors: CodeErrors) -> Result<Vec<u8>, LicenseError> {
+    let cleaned: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let mut bytes = BASE32_NOPAD
+        .decode(cleaned.as_bytes())
+        .map_err(|_| LicenseError::BadCode(errors[0]))?;
+    if bytes.len() != len {
+        return Err(LicenseError::BadCode(errors[1]));
+    }
+    if bytes[0] != version {
+        return Err(LicenseError::BadCode(errors[2]));
+    }
+    let crc = bytes.split_off(len - 2);
+    if CRC16.checksum(&bytes).to_be_bytes() != crc[..] {
+        return Err(LicenseError::BadCode(errors[3]));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SigningKey;
+
+    use super::*;
+    use crate::{Claims, Edition, SignedLicense};
+
+    fn machine(id: &str) -> MachineFingerprint {
+        MachineFingerprint {
+            machine_id: Some(id.to_string()),
+            hostname: Some("host".to_string()),
+            mac_addresses: vec!["00:11:22:33:44:55".to_string()],
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// What the vendor portal does with a request code.
+    fn respond(request: &str, key: &SigningKey) -> String {
+        let claims = Claims {
+            license_id: "CV-OFF-0001".to_string(),
+            licensee: "Air Gap".to_string(),
+            edition: Edition::Enterprise,
+            issued: date(2025, 1, 1),
+            expires: None,
+            seats: 1,
+            features: Vec::new(),
+            machine: Some(parse_request_code(request).unwrap()),
+            grace_days: None,
+        };
+        SignedLicense::sign(&claims, key).to_code()
+    }
+
+    #[test]
+    fn request_and_response_round_trip() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let here = machine("here");
+        let request = request_code(&here);
+        assert_eq!(request.split('-').count(), 6);
+        assert_eq!(parse_request_code(&request), Ok(here.machine_id()));
+        assert_eq!(parse_request_code(&request.to_lowercase().replace('-', " ")), Ok(here.machine_id()));
+
+        let response = SignedLicense::from_code(&respond(&request, &key)).unwrap();
+        let claims = response.verify_with(&key.verifying_key()).unwrap();
+        assert!(response.accept(claims, date(2025, 6, 1), &here).is_ok());
+    }
+
+    #[test]
+    fn response_is_refused_on_another_machine() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let response = SignedLicense::from_code(&respond(&request_code(&machine("here")), &key)).unwrap();
+        let claims = response.verify_with(&key.verifying_key()).unwrap();
+        assert_eq!(
+            response.accept(claims, date(2025, 6, 1), &machine("elsewhere")),
+            Err(LicenseError::WrongMachine)
+        );
+    }
+
+    #[test]
+    fn rejects_damaged_request_codes() {
+        let request = request_code(&machine("here"));
+        let mut typo: Vec<char> = request.chars().collect();
+        typo[10] = if typo[10] == 'A' { 'B' } else { 'A' };
+        let typo: String = typo.into_iter().collect();
+        assert_eq!(parse_request_code(&typo), Err(LicenseError::BadCode(REQUEST_ERRORS[3])));
+        assert_eq!(parse_request_code(&request[..20]), Err(LicenseError::BadCode(REQUEST_ERRORS[1])));
+        assert_eq!(parse_request_code("!!!!"), Err(LicenseError::BadCode(REQUEST_ERRORS[1])));
+        let license = License::Key { key: "4I1Q-ZKHI-HQ28".to_string(), edition: Edition::Enterprise };
+        let receipt = deactivation_code(&machine("here"), &license, date(2025, 1, 1));
+        assert_eq!(parse_request_code(&receipt), Err(LicenseError::BadCode(REQUEST_ERRORS[1])));
+    }
+
+    #[test]
+    fn receipt_round_trips() {
+        let here = machine("here");
+        let license = License::Key { key: "4I1Q-ZKHI-HQ28".to_string(), edition: Edition::Enterprise };
+        let receipt = parse_deactivation_code(&deactivation_code(&here, &license, date(2025, 3, 14))).unwrap();
+        assert!(receipt.is_from_machine(&here.machine_id()));
+        assert_eq!(receipt.date, date(2025, 3, 14));
+        assert!(receipt.is_for_key("4i1q zkhi hq28"));
+        assert!(!receipt.is_for_key("ABCD-EFGH-JKLM"));
+        assert!(!receipt.is_for_license_id("CV-OFF-0001"));
+    }
+
+    #[test]
+    fn receipt_is_bound_to_its_machine() {
+        let license = License::Key { key: "4I1Q-ZKHI-HQ28".to_string(), edition: Edition::Enterprise };
+        let code = deactivation_code(&machine("here"), &license, date(2025, 3, 14));
+        let receipt = parse_deactivation_code(&code).unwrap();
+        assert!(!receipt.is_from_machine(&machine("elsewhere").machine_id()));
+        assert!(!receipt.is_from_machine("not hex"));
+
+        // Moving the date keeps the CRC valid but breaks the MAC.
+        let mut bytes = BASE32_NOPAD.decode(code.replace('-', "").as_bytes()).unwrap();
+        bytes.truncate(RECEIPT_LEN - 2);
+        bytes[RECEIPT_BODY_LEN - 1] ^= 1;
+        let forged = parse_deactivation_code(&encode(bytes)).unwrap();
+        assert_ne!(forged.date, receipt.date);
+        assert!(!forged.is_from_machine(&machine("here").machine_id()));
+    }
+}
@@ -0,0 +1,140 @@
+//! Wire types for the online activation service.
+//!
+//! Every endpoint takes and returns JSON over HTTP `POST`:
+//!
+//! | Endpoint          | Request                   | Response                   |
+//! |-------------------|---------------------------|----------------------------|
+//! | `/v1/activate`    | [`ActivateRequest`]       | [`ActivateResponse`]       |
+//! | `/v1/deactivate`  | [`DeactivateRequest`]     | [`DeactivateResponse`]     |
+//! | `/v1/heartbeat`   | [`HeartbeatRequest`]      | [`HeartbeatResponse`]      |
+//! | `/v1/revocation`  | [`RevocationRequest`]     | [`RevocationResponse`]     |
+//! | `/v1/revocations` | [`RevocationListRequest`] | [`RevocationListResponse`] |
+//!
+//! Floating licenses are served by a separate lease service:
+//!
+//! | Endpoint            | Request                 | Response                 |
+//! |---------------------|-------------------------|--------------------------|
+//! | `/v1/lease/acquire` | [`LeaseRequest`]        | [`LeaseResponse`]        |
+//! | `/v1/lease/renew`   | [`RenewLeaseRequest`]   | [`RenewLeaseResponse`]   |
+//! | `/v1/lease/release` | [`ReleaseLeaseRequest`] | [`ReleaseLeaseResponse`] |
+//!
+//! Failures use a 4xx/5xx status with an [`ErrorResponse`] body. The lease
+//! service answers `409 Conflict` when every seat is taken and `404 Not Found`
+//! when renewing a lease that has already expired.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{SignedLicense, SignedRevocationList};
+
+/// Where the app looks for the activation service unless
+/// `CYBERVAULT_ACTIVATION_URL` is set.
+pub const DEFAULT_ACTIVATION_URL: &str = "http://127.0.0.1:7878";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivateRequest {
+    pub key: String,
+    /// [`MachineFingerprint::machine_id`](crate::MachineFingerprint::machine_id)
+    pub machine: String,
+    pub app_version: String,
+}
+
+/// A license bound to the requesting machine, plus the handle used for
+/// heartbeats and deactivation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivateResponse {
+    pub activation_id: String,
+    pub license: SignedLicense,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeactivateRequest {
+    pub activation_id: String,
+    pub machine: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeactivateResponse {
+    pub released: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatRequest {
+    pub activation_id: String,
+    pub machine: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatResponse {
+    /// `false` once the activation was deactivated or its license revoked.
+    pub valid: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationRequest {
+    pub license_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationResponse {
+    pub revoked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationListRequest {
+    /// Serial of the list the client already has.
+    pub since_serial: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationListResponse {
+    /// `None` when the client's list is already current.
+    pub list: Option<SignedRevocationList>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseRequest {
+    pub machine: String,
+    pub app_version: String,
+}
+
+/// A seat from the floating pool. The license is bound to the requesting
+/// machine but is only honoured while the lease is kept alive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseResponse {
+    pub lease_id: String,
+    /// Seconds until the lease expires unless renewed.
+    pub lease_secs: u64,
+    pub license: SignedLicense,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenewLeaseRequest {
+    pub lease_id: String,
+    pub machine: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenewLeaseResponse {
+    pub lease_secs: u64,
+    /// The lease's license reissued with a fresh expiry; it replaces the one
+    /// from the acquire or the previous renewal.
+    pub license: SignedLicense,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseLeaseRequest {
+    pub lease_id: String,
+    pub machine: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseLeaseResponse {
+    pub released: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
@@ -0,0 +1,140 @@
+use chrono::NaiveDate;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::signed::{sign_payload, vendor_key, verify_payload};
+use crate::{KeyFormat, License, LicenseError};
+
+/// Product keys and license IDs the vendor has withdrawn.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationList {
+    /// Grows with every published list, so an older list never replaces a
+    /// newer one.
+    pub serial: u64,
+    pub issued: Option<NaiveDate>,
+    /// [`hash_key`](Self::hash_key) of each revoked product key, so that the
+    /// list itself does not hand out the leaked keys.
+    #[serde(default)]
+    pub keys: Vec<String>,
+    #[serde(default)]
+    pub licenses: Vec<String>,
+}
+
+impl RevocationList {
+    /// SHA-256 (hex) of a normalized product key as it appears in
+    /// [`keys`](Self::keys).
+    pub fn hash_key(key: &str) -> String {
+        let key = KeyFormat::detect(key).normalize(key);
+        crate::fingerprint::hex_encode(&Sha256::digest(key.as_bytes()))
+    }
+
+    pub fn is_revoked(&self, license: &License) -> bool {
+        match license {
+            License::Key { key, .. } => self.keys.contains(&Self::hash_key(key)),
+            License::Signed { claims, .. } => self.licenses.contains(&claims.license_id),
+        }
+    }
+
+    /// Passes `license` through unless it is on the list.
+    pub fn check(&self, license: License) -> Result<License, LicenseError> {
+        if self.is_revoked(&license) {
+            Err(LicenseError::Revoked)
+        } else {
+            Ok(license)
+        }
+    }
+}
+
+/// A revocation list and an Ed25519 signature over its JSON encoding, laid
+/// out like [`SignedLicense`](crate::SignedLicense).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedRevocationList {
+    pub payload: String,
+    pub signature: String,
+}
+
+impl SignedRevocationList {
+    pub fn sign(list: &RevocationList, key: &SigningKey) -> Self {
+        let payload = serde_json::to_vec(list).expect("revocation list serializes to JSON");
+        let (payload, signature) = sign_payload(&payload, key);
+        Self { payload, signature }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, LicenseError> {
+        serde_json::from_str(text).map_err(|e| LicenseError::Malformed(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("revocation list serializes to JSON")
+    }
+
+    pub fn verify_with(&self, key: &VerifyingKey) -> Result<RevocationList, LicenseError> {
+        let payload = verify_payload(&self.payload, &self.signature, key)?;
+        serde_json::from_slice(&payload).map_err(|e| LicenseError::Malformed(e.to_string()))
+    }
+
+    /// Checks the signature against the embedded vendor key.
+    pub fn verify(&self) -> Result<RevocationList, LicenseError> {
+        self.verify_with(&vendor_key()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Claims, Edition, SignedLicense};
+
+    fn signed(license_id: &str) -> License {
+        let claims = Claims {
+            license_id: license_id.to_string(),
+            licensee: "Test User".to_string(),
+            edition: Edition::Pro,
+            issued: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            expires: None,
+            seats: 1,
+            features: Vec::new(),
+            machine: None,
+            grace_days: None,
+        };
+        let file = SignedLicense::sign(&claims, &SigningKey::from_bytes(&[7; 32]));
+        License::Signed { claims, file }
+    }
+
+    fn key(key: &str) -> License {
+        License::Key { key: key.to_string(), edition: Edition::Enterprise }
+    }
+
+    #[test]
+    fn check_rejects_listed_keys_and_licenses() {
+        let list = RevocationList {
+            serial: 1,
+            issued: None,
+            keys: vec![RevocationList::hash_key("4I1Q-ZKHI-HQ28")],
+            licenses: vec!["CV-REVOKED".to_string()],
+        };
+        assert_eq!(list.check(key("4I1Q-ZKHI-HQ28")), Err(LicenseError::Revoked));
+        assert_eq!(list.check(signed("CV-REVOKED")), Err(LicenseError::Revoked));
+        assert_eq!(list.check(key("ABCD-EFGH-JKLM")), Ok(key("ABCD-EFGH-JKLM")));
+        assert_eq!(list.check(signed("CV-OTHER")), Ok(signed("CV-OTHER")));
+        assert!(RevocationList::default().check(key("4I1Q-ZKHI-HQ28")).is_ok());
+    }
+
+    #[test]
+    fn hash_key_ignores_how_the_key_is_typed() {
+        let hash = RevocationList::hash_key("4I1Q-ZKHI-HQ28");
+        assert_eq!(RevocationList::hash_key("4i1q zkhi hq28"), hash);
+        assert_eq!(RevocationList::hash_key("4I1QZKHIHQ28"), hash);
+        assert!(!hash.contains("4I1Q"));
+    }
+
+    #[test]
+    fn signed_list_round_trips() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let list = RevocationList { serial: 3, licenses: vec!["CV-REVOKED".to_string()], ..Default::default() };
+        let signed = SignedRevocationList::parse(&SignedRevocationList::sign(&list, &key).to_json()).unwrap();
+        assert_eq!(signed.verify_with(&key.verifying_key()), Ok(list));
+        let other = SigningKey::from_bytes(&[8; 32]);
+        assert_eq!(signed.verify_with(&other.verifying_key()), Err(LicenseError::BadSignature));
+    }
+}
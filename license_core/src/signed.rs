@@ -0,0 +1,247 @@
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use chrono::NaiveDate;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::{Edition, LicenseError, MachineFingerprint};
+
+// Public half of the vendor signing key, injected at build time; see
+// build.rs. `None` when the build was given no key.
+include!(concat!(env!("OUT_DIR"), "/vendor_key.rs"));
+
+/// What a signed license grants and to whom.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claims {
+    pub license_id: String,
+    pub licensee: String,
+    pub edition: Edition,
+    pub issued: NaiveDate,
+    pub expires: Option<NaiveDate>,
+    pub seats: u32,
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// [`MachineFingerprint::machine_id`] of the only machine this license is
+    /// valid on, for licenses issued through offline activation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine: Option<String>,
+    /// Days the license keeps working after `expires` before the app turns
+    /// read-only; [`DEFAULT_GRACE_DAYS`](crate::DEFAULT_GRACE_DAYS) if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grace_days: Option<u32>,
+}
+
+/// A license file: the JSON-encoded [`Claims`] and an Ed25519 signature over
+/// exactly those bytes, both base64 encoded.
+///
+/// The signature covers the payload bytes as issued, so verification never
+/// has to re-serialize the claims.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedLicense {
+    pub payload: String,
+    pub signature: String,
+}
+
+impl SignedLicense {
+    /// Signs `claims` with the vendor key.
+    pub fn sign(claims: &Claims, key: &SigningKey) -> Self {
+        let payload = serde_json::to_vec(claims).expect("claims serialize to JSON");
+        let (payload, signature) = sign_payload(&payload, key);
+        Self { payload, signature }
+    }
+
+    /// Parses the on-disk (JSON) representation of a license file.
+    pub fn parse(text: &str) -> Result<Self, LicenseError> {
+        serde_json::from_str(text).map_err(|e| LicenseError::Malformed(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("license serializes to JSON")
+    }
+
+    /// Compact single-line form (`payload.signature`, base64url) used for
+    /// offline activation response codes.
+    pub fn to_code(&self) -> String {
+        let reencode = |s: &str| URL_SAFE_NO_PAD.encode(STANDARD.decode(s).expect("fields are base64"));
+        format!("{}.{}", reencode(&self.payload), reencode(&self.signature))
+    }
+
+    /// Parses [`to_code`](Self::to_code) output. Whitespace (from wrapped
+    /// pastes) is ignored.
+    pub fn from_code(code: &str) -> Result<Self, LicenseError> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let (payload, signature) = code.split_once('.').ok_or(LicenseError::BadCode("missing '.' separator"))?;
+        let reencode = |s: &str| {
+            URL_SAFE_NO_PAD
+                .decode(s)
+                .map(|bytes| STANDARD.encode(bytes))
+                .map_err(|_| LicenseError::BadCode("invalid base64"))
+        };
+        Ok(Self { payload: reencode(payload)?, signature: reencode(signature)? })
+    }
+
+    /// Checks the signature against `key` and decodes the claims. Dates are
+    /// not checked here; see [`verify_license_file`].
+    pub fn verify_with(&self, key: &VerifyingKey) -> Result<Claims, LicenseError> {
+        let payload = verify_payload(&self.payload, &self.signature, key)?;
+        serde_json::from_slice(&payload).map_err(|e| LicenseError::Malformed(e.to_string()))
+    }
+
+    /// Checks the signature against the embedded [`VENDOR_PUBLIC_KEY`], and
+    /// fails with [`LicenseError::NoVendorKey`] in builds without one.
+    pub fn verify(&self) -> Result<Claims, LicenseError> {
+        self.verify_with(&vendor_key()?)
+    }
+
+    /// Verifies the signature and checks that the license is valid on `today`
+    /// and, if it is machine-bound, on `machine`.
+    pub fn validate(self, today: NaiveDate, machine: &MachineFingerprint) -> Result<crate::License, LicenseError> {
+        let license = self.validate_ignoring_expiry(today, machine)?;
+        if let Some(expires) = license.expires()
+            && today > expires
+        {
+            return Err(LicenseError::Expired { on: expires });
+        }
+        Ok(license)
+    }
+
+    /// Like [`validate`](Self::validate), but accepts a license past its
+    /// expiry date. Used for licenses that were already in use, which degrade
+    /// according to their [`Standing`](crate::Standing) instead.
+    pub fn validate_ignoring_expiry(
+        self,
+        today: NaiveDate,
+        machine: &MachineFingerprint,
+    ) -> Result<crate::License, LicenseError> {
+        let claims = self.verify()?;
+        self.accept(claims, today, machine)
+    }
+
+    /// The date and machine checks of [`validate_ignoring_expiry`](Self::validate_ignoring_expiry)
+    /// for `claims` already verified from this file.
+    pub(crate) fn accept(
+        self,
+        claims: Claims,
+        today: NaiveDate,
+        machine: &MachineFingerprint,
+    ) -> Result<crate::License, LicenseError> {
+        if let Some(bound) = &claims.machine
+            && *bound != machine.machine_id()
+        {
+            return Err(LicenseError::WrongMachine);
+        }
+        if today < claims.issued {
+            return Err(LicenseError::NotYetValid { from: claims.issued });
+        }
+        Ok(crate::License::Signed { claims, file: self })
+    }
+}
+
+/// Parses and verifies a license file; see [`SignedLicense::validate`].
+pub fn verify_license_file(
+    text: &str,
+    today: NaiveDate,
+    machine: &MachineFingerprint,
+) -> Result<crate::License, LicenseError> {
+    SignedLicense::parse(text)?.validate(today, machine)
+}
+
+/// Signs `payload` and returns it with its signature, both base64 encoded.
+pub(crate) fn sign_payload(payload: &[u8], key: &SigningKey) -> (String, String) {
+    let signature = key.sign(payload);
+    (STANDARD.encode(payload), STANDARD.encode(signature.to_bytes()))
+}
+
+/// Decodes a base64 payload after checking its base64 signature against `key`.
+pub(crate) fn verify_payload(payload: &str, signature: &str, key: &VerifyingKey) -> Result<Vec<u8>, LicenseError> {
+    let payload = STANDARD
+        .decode(payload)
+        .map_err(|e| LicenseError::Malformed(e.to_string()))?;
+    let signature = STANDARD
+        .decode(signature)
+        .map_err(|e| LicenseError::Malformed(e.to_string()))?;
+    let signature =
+        Signature::from_slice(&signature).map_err(|_| LicenseError::BadSignature)?;
+    key.verify(&payload, &signature)
+        .map_err(|_| LicenseError::BadSignature)?;
+    Ok(payload)
+}
+
+pub(crate) fn vendor_key() -> Result<VerifyingKey, LicenseError> {
+    let key = VENDOR_PUBLIC_KEY.ok_or(LicenseError::NoVendorKey)?;
+    VerifyingKey::from_bytes(&key).map_err(|_| LicenseError::NoVendorKey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            license_id: "CV-TEST-0001".to_string(),
+            licensee: "Test User".to_string(),
+            edition: Edition::Pro,
+            issued: date(2025, 1, 1),
+            expires: Some(date(2026, 1, 1)),
+            seats: 1,
+            features: Vec::new(),
+            machine: None,
+            grace_days: None,
+        }
+    }
+
+    fn machine(id: &str) -> MachineFingerprint {
+        MachineFingerprint { machine_id: Some(id.to_string()), hostname: None, mac_addresses: Vec::new() }
+    }
+
+    #[test]
+    fn verifies_its_own_signature() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let file = SignedLicense::sign(&claims(), &key);
+        assert_eq!(file.verify_with(&key.verifying_key()), Ok(claims()));
+        let parsed = SignedLicense::parse(&file.to_json()).unwrap();
+        assert_eq!(parsed.verify_with(&key.verifying_key()), Ok(claims()));
+        let decoded = SignedLicense::from_code(&file.to_code()).unwrap();
+        assert_eq!(decoded, file);
+    }
+
+    #[test]
+    fn rejects_tampering() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let file = SignedLicense::sign(&claims(), &key);
+
+        let mut upgraded = claims();
+        upgraded.edition = Edition::Enterprise;
+        let payload = STANDARD.encode(serde_json::to_vec(&upgraded).unwrap());
+        let forged = SignedLicense { payload, signature: file.signature.clone() };
+        assert_eq!(forged.verify_with(&key.verifying_key()), Err(LicenseError::BadSignature));
+
+        let mut signature = STANDARD.decode(&file.signature).unwrap();
+        signature[0] ^= 1;
+        let forged = SignedLicense { payload: file.payload.clone(), signature: STANDARD.encode(signature) };
+        assert_eq!(forged.verify_with(&key.verifying_key()), Err(LicenseError::BadSignature));
+
+        let other = SigningKey::from_bytes(&[8; 32]);
+        assert_eq!(file.verify_with(&other.verifying_key()), Err(LicenseError::BadSignature));
+    }
+
+    #[test]
+    fn checks_the_machine_and_issue_date() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let here = machine("here");
+        let mut bound = claims();
+        bound.machine = Some(here.machine_id());
+        let file = SignedLicense::sign(&bound, &key);
+        let accept = |today, machine: &MachineFingerprint| file.clone().accept(bound.clone(), today, machine);
+
+        assert!(accept(date(2025, 6, 1), &here).is_ok());
+        assert_eq!(accept(date(2025, 6, 1), &machine("elsewhere")), Err(LicenseError::WrongMachine));
+        assert_eq!(accept(date(2024, 12, 31), &here), Err(LicenseError::NotYetValid { from: date(2025, 1, 1) }));
+        // Expiry is left to `validate` and the license's standing.
+        assert!(accept(date(2027, 1, 1), &here).is_ok());
+    }
+}
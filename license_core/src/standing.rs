@@ -0,0 +1,50 @@
+//! What a license still allows once its expiry date has passed.
+
+use chrono::{Days, NaiveDate};
+
+use crate::{Entitlements, License};
+
+/// Grace period for licenses whose claims do not set `grace_days`.
+pub const DEFAULT_GRACE_DAYS: u32 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Standing {
+    Active,
+    /// Expired, but everything keeps working through `until`.
+    Grace { until: NaiveDate },
+    /// Past the grace period: existing data can be viewed and exported, but
+    /// not changed.
+    ReadOnly { since: NaiveDate },
+}
+
+impl License {
+    /// Last day the license is valid on, if it expires at all.
+    pub fn expires(&self) -> Option<NaiveDate> {
+        self.claims().and_then(|claims| claims.expires)
+    }
+
+    pub fn grace_days(&self) -> u32 {
+        self.claims().and_then(|claims| claims.grace_days).unwrap_or(DEFAULT_GRACE_DAYS)
+    }
+
+    pub fn standing(&self, today: NaiveDate) -> Standing {
+        let Some(expires) = self.expires() else { return Standing::Active };
+        let until = expires + Days::new(self.grace_days() as u64);
+        if today <= expires {
+            Standing::Active
+        } else if today <= until {
+            Standing::Grace { until }
+        } else {
+            Standing::ReadOnly { since: until + Days::new(1) }
+        }
+    }
+
+    /// [`entitlements`](Self::entitlements), made read-only once the grace
+    /// period is over.
+    pub fn entitlements_on(&self, today: NaiveDate) -> Entitlements {
+        match self.standing(today) {
+            Standing::ReadOnly { .. } => self.entitlements().read_only(),
+            Standing::Active | Standing::Grace { .. } => self.entitlements(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+
+/// Challenge difficulty the crate was built for, selected with the `easy`,
+/// `medium` and `hard` cargo features. When several are enabled the hardest
+/// wins; with none, the build is easy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    /// Debug output of the key hash and full symbols.
+    Easy,
+    /// No debug output, stripped symbols and a concealed target constant.
+    Medium,
+    /// Medium plus encrypted UI strings and a key check that runs on the
+    /// [`vm`](crate::vm).
+    Hard,
+}
+
+impl Tier {
+    pub const ACTIVE: Tier = if cfg!(feature = "hard") {
+        Tier::Hard
+    } else if cfg!(feature = "medium") {
+        Tier::Medium
+    } else {
+        Tier::Easy
+    };
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Tier::Easy => "Easy",
+            Tier::Medium => "Medium",
+            Tier::Hard => "Hard",
+        })
+    }
+}
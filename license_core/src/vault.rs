@@ -0,0 +1,258 @@
+//! `.cvault` files: streaming AES-256-GCM encryption under a key derived from
+//! a passphrase with Argon2id. The format is specified in
+//! `docs/cvault-format.md`, with test vectors in `docs/cvault-vectors.json`.
+//!
+//! A file is a fixed-size [`Header`] followed by the plaintext in chunks of
+//! `chunk_size` bytes, each sealed on its own with a 16-byte tag. Chunk
+//! nonces are the header's nonce prefix, a big-endian chunk counter and a
+//! byte marking the last chunk (the STREAM construction), so chunks cannot be
+//! reordered, dropped or cut off at the end. Every chunk authenticates the
+//! header as associated data.
+
+use std::io::{self, Read, Write};
+
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::stream::{DecryptorBE32, EncryptorBE32};
+use aes_gcm::aead::{KeyInit, OsRng, Payload};
+use aes_gcm::Aes256Gcm;
+use argon2::{Argon2, Params};
+use thiserror::Error;
+
+pub const MAGIC: &[u8; 6] = b"CVAULT";
+pub const VERSION: u8 = 1;
+pub const HEADER_LEN: usize = 48;
+pub const TAG_LEN: usize = 16;
+pub const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+/// Cipher and KDF ids; version 1 defines one of each.
+pub const CIPHER_AES_256_GCM: u8 = 1;
+pub const KDF_ARGON2ID: u8 = 1;
+/// Limits a reader enforces so a crafted header cannot exhaust memory or
+/// keep the key derivation running for hours.
+const MAX_CHUNK_SIZE: u32 = 16 * 1024 * 1024;
+const MAX_KDF_MEMORY: u32 = 1024 * 1024;
+const MAX_KDF_ITERATIONS: u32 = 16;
+const MAX_KDF_PARALLELISM: u32 = 16;
+
+#[derive(Debug, Error)]
+pub enum VaultError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("not a .cvault file")]
+    NotAVault,
+    #[error(".cvault version {0} is not supported")]
+    UnsupportedVersion(u8),
+    #[error("cipher {0} is not supported")]
+    UnsupportedCipher(u8),
+    #[error("key derivation function {0} is not supported")]
+    UnsupportedKdf(u8),
+    #[error("invalid header: {0}")]
+    BadHeader(&'static str),
+    #[error("key derivation failed: {0}")]
+    Kdf(String),
+    #[error("encryption failed")]
+    Encrypt,
+    /// The first chunk is usually where a wrong passphrase shows.
+    #[error("wrong passphrase or damaged file")]
+    Authentication,
+    #[error("file is truncated")]
+    Truncated,
+}
+
+impl VaultError {
+    /// The name of the error in `docs/cvault-vectors.json`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            VaultError::Io(_) => "io",
+            VaultError::NotAVault => "not_a_vault",
+            VaultError::UnsupportedVersion(_) => "unsupported_version",
+            VaultError::UnsupportedCipher(_) => "unsupported_cipher",
+            VaultError::UnsupportedKdf(_) => "unsupported_kdf",
+            VaultError::BadHeader(_) => "bad_header",
+            VaultError::Kdf(_) => "kdf",
+            VaultError::Encrypt => "encrypt",
+            VaultError::Authentication => "authentication",
+            VaultError::Truncated => "truncated",
+        }
+    }
+}
+
+/// Argon2id cost parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory in KiB.
+    pub memory: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self { memory: 19 * 1024, iterations: 2, parallelism: 1 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub kdf: KdfParams,
+    pub salt: [u8; 16],
+    pub nonce_prefix: [u8; 7],
+    pub chunk_size: u32,
+}
+
+impl Header {
+    /// A header with a fresh random salt and nonce prefix.
+    pub fn generate() -> Self {
+        let mut salt = [0u8; 16];
+        let mut nonce_prefix = [0u8; 7];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce_prefix);
+        Self { kdf: KdfParams::default(), salt, nonce_prefix, chunk_size: DEFAULT_CHUNK_SIZE }
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut bytes = [0u8; HEADER_LEN];
+        bytes[..6].copy_from_slice(MAGIC);
+        bytes[6] = VERSION;
+        bytes[7] = CIPHER_AES_256_GCM;
+        bytes[8] = KDF_ARGON2ID;
+        bytes[9..13].copy_from_slice(&self.kdf.memory.to_le_bytes());
+        bytes[13..17].copy_from_slice(&self.kdf.iterations.to_le_bytes());
+        bytes[17..21].copy_from_slice(&self.kdf.parallelism.to_le_bytes());
+        bytes[21..37].copy_from_slice(&self.salt);
+        bytes[37..44].copy_from_slice(&self.nonce_prefix);
+        bytes[44..48].copy_from_slice(&self.chunk_size.to_le_bytes());
+        bytes
+    }
+
+    pub fn parse(bytes: &[u8; HEADER_LEN]) -> Result<Self, VaultError> {
+        if &bytes[..6] != MAGIC {
+            return Err(VaultError::NotAVault);
+        }
+        match (bytes[6], bytes[7], bytes[8]) {
+            (VERSION, CIPHER_AES_256_GCM, KDF_ARGON2ID) => {}
+            (VERSION, CIPHER_AES_256_GCM, kdf) => return Err(VaultError::UnsupportedKdf(kdf)),
+            (VERSION, cipher, _) => return Err(VaultError::UnsupportedCipher(cipher)),
+            (version, _, _) => return Err(VaultError::UnsupportedVersion(version)),
+        }
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().expect("four bytes"));
+        let header = Self {
+            kdf: KdfParams { memory: u32_at(9), iterations: u32_at(13), parallelism: u32_at(17) },
+            salt: bytes[21..37].try_into().expect("16 bytes"),
+            nonce_prefix: bytes[37..44].try_into().expect("7 bytes"),
+            chunk_size: u32_at(44),
+        };
+        if header.chunk_size == 0 || header.chunk_size > MAX_CHUNK_SIZE {
+            return Err(VaultError::BadHeader("chunk size out of range"));
+        }
+        if header.kdf.memory > MAX_KDF_MEMORY {
+            return Err(VaultError::BadHeader("key derivation needs too much memory"));
+        }
+        if header.kdf.iterations > MAX_KDF_ITERATIONS {
+            return Err(VaultError::BadHeader("key derivation takes too many iterations"));
+        }
+        if header.kdf.parallelism > MAX_KDF_PARALLELISM {
+            return Err(VaultError::BadHeader("key derivation uses too many lanes"));
+        }
+        Ok(header)
+    }
+
+    /// The AES-256 key for `passphrase` under this header's salt and costs.
+    pub fn derive_key(&self, passphrase: &str) -> Result<[u8; 32], VaultError> {
+        let params = Params::new(self.kdf.memory, self.kdf.iterations, self.kdf.parallelism, Some(32))
+            .map_err(|e| VaultError::Kdf(e.to_string()))?;
+        let mut key = [0u8; 32];
+        Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &self.salt, &mut key)
+            .map_err(|e| VaultError::Kdf(e.to_string()))?;
+        Ok(key)
+    }
+}
+
+/// Encrypts everything `reader` yields into `writer`. `progress` is called
+/// with the number of plaintext bytes done after every chunk. Returns the
+/// plaintext length.
+pub fn encrypt(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    passphrase: &str,
+    header: &Header,
+    mut progress: impl FnMut(u64),
+) -> Result<u64, VaultError> {
+    let header_bytes = header.to_bytes();
+    let key = header.derive_key(passphrase)?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key));
+    let mut stream = EncryptorBE32::from_aead(cipher, GenericArray::from_slice(&header.nonce_prefix));
+    writer.write_all(&header_bytes)?;
+
+    let chunk_size = header.chunk_size as usize;
+    let mut done = 0u64;
+    let mut chunk = read_chunk(reader, chunk_size)?;
+    loop {
+        // A full chunk may be the last one; only the next read can tell.
+        let next = if chunk.len() == chunk_size { read_chunk(reader, chunk_size)? } else { Vec::new() };
+        let payload = Payload { msg: &chunk, aad: &header_bytes };
+        done += chunk.len() as u64;
+        if next.is_empty() {
+            writer.write_all(&stream.encrypt_last(payload).map_err(|_| VaultError::Encrypt)?)?;
+            progress(done);
+            return Ok(done);
+        }
+        writer.write_all(&stream.encrypt_next(payload).map_err(|_| VaultError::Encrypt)?)?;
+        progress(done);
+        chunk = next;
+    }
+}
+
+/// Decrypts a whole `.cvault` stream into `writer`. Each chunk is verified
+/// before it is written, but a damaged file is only detected when its chunk
+/// is reached, so on error the output must be discarded. `progress` is
+/// called with the number of plaintext bytes written. Returns the plaintext
+/// length.
+pub fn decrypt(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    passphrase: &str,
+    mut progress: impl FnMut(u64),
+) -> Result<u64, VaultError> {
+    let mut header_bytes = [0u8; HEADER_LEN];
+    let header_read = read_chunk(reader, HEADER_LEN)?;
+    if header_read.len() < HEADER_LEN {
+        return Err(if header_read.starts_with(MAGIC) { VaultError::Truncated } else { VaultError::NotAVault });
+    }
+    header_bytes.copy_from_slice(&header_read);
+    let header = Header::parse(&header_bytes)?;
+    let key = header.derive_key(passphrase)?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key));
+    let mut stream = DecryptorBE32::from_aead(cipher, GenericArray::from_slice(&header.nonce_prefix));
+
+    let sealed_size = header.chunk_size as usize + TAG_LEN;
+    let mut done = 0u64;
+    let mut chunk = read_chunk(reader, sealed_size)?;
+    loop {
+        if chunk.len() < TAG_LEN {
+            return Err(VaultError::Truncated);
+        }
+        let next = if chunk.len() == sealed_size { read_chunk(reader, sealed_size)? } else { Vec::new() };
+        let payload = Payload { msg: &chunk, aad: &header_bytes };
+        if next.is_empty() {
+            let plain = stream.decrypt_last(payload).map_err(|_| VaultError::Authentication)?;
+            writer.write_all(&plain)?;
+            done += plain.len() as u64;
+            progress(done);
+            return Ok(done);
+        }
+        let plain = stream.decrypt_next(payload).map_err(|_| VaultError::Authentication)?;
+        writer.write_all(&plain)?;
+        done += plain.len() as u64;
+        progress(done);
+        chunk = next;
+    }
+}
+
+/// Reads up to `size` bytes, fewer only at the end of the input.
+fn read_chunk(reader: &mut impl Read, size: usize) -> io::Result<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(size);
+    reader.take(size as u64).read_to_end(&mut chunk)?;
+    Ok(chunk)
+}
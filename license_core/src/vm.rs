@@ -0,0 +1,203 @@
+//! A small stack machine for challenge levels whose check is shipped as
+//! bytecode instead of compiled into the app.
+//!
+//! The stack holds `u32` values. A program accepts its input when it reaches
+//! `HALT` with a non-zero value on top of the stack. Immediates are little
+//! endian; jump targets are absolute byte offsets.
+//!
+//! | Op   | Mnemonic    | Effect                                          |
+//! |------|-------------|-------------------------------------------------|
+//! | `00` | `HALT`      | stop; accept if the top of the stack is non-zero |
+//! | `01` | `PUSH imm32`| push `imm32`                                    |
+//! | `02` | `POP`       | drop the top value                              |
+//! | `03` | `DUP`       | `a` → `a a`                                     |
+//! | `04` | `SWAP`      | `a b` → `b a`                                   |
+//! | `05` | `ROT`       | `a b c` → `b c a`                               |
+//! | `06` | `LEN`       | push the input length                           |
+//! | `07` | `LOAD`      | `i` → input byte `i`, or 0 past the end         |
+//! | `08` | `ADD`       | `a b` → `a + b` (wrapping)                      |
+//! | `09` | `SUB`       | `a b` → `a - b` (wrapping)                      |
+//! | `0A` | `MUL`       | `a b` → `a * b` (wrapping)                      |
+//! | `0B` | `XOR`       | `a b` → `a ^ b`                                 |
+//! | `0C` | `AND`       | `a b` → `a & b`                                 |
+//! | `0D` | `OR`        | `a b` → `a \| b`                                |
+//! | `0E` | `ROL`       | `a n` → `a` rotated left by `n`                 |
+//! | `0F` | `EQ`        | `a b` → 1 if equal, else 0                      |
+//! | `10` | `MIX`       | `x b` → [`mix`]`(x, b)`                         |
+//! | `11` | `JMP imm16` | jump to `imm16`                                 |
+//! | `12` | `JZ imm16`  | pop; jump to `imm16` if it was zero             |
+
+use crate::checksum::mix;
+
+const MAX_STEPS: usize = 100_000;
+const MAX_STACK: usize = 256;
+
+/// Why a program stopped without an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VmError {
+    UnknownOpcode { at: usize, op: u8 },
+    /// An immediate or jump target runs past the end of the program.
+    Truncated { at: usize },
+    StackUnderflow { at: usize },
+    StackOverflow { at: usize },
+    StepLimit,
+}
+
+/// Runs `program` on `input` and returns whether it accepted.
+pub fn run(program: &[u8], input: &[u8]) -> Result<bool, VmError> {
+    execute(program, input).map(|stack| stack.last().is_some_and(|&top| top != 0))
+}
+
+/// Runs `program` on `input` and returns the stack it halted with, bottom
+/// first. A challenge level keys its sealed reward with the stack of an
+/// accepted answer, so a program should leave a value that every answer it
+/// accepts shares below its verdict.
+pub fn execute(program: &[u8], input: &[u8]) -> Result<Vec<u32>, VmError> {
+    let mut stack: Vec<u32> = Vec::new();
+    let mut pc = 0;
+    for _ in 0..MAX_STEPS {
+        let at = pc;
+        let Some(&op) = program.get(pc) else { return Err(VmError::Truncated { at }) };
+        pc += 1;
+        let pop = |stack: &mut Vec<u32>| stack.pop().ok_or(VmError::StackUnderflow { at });
+        match op {
+            0x00 => return Ok(stack),
+            0x01 => {
+                let imm = program.get(pc..pc + 4).ok_or(VmError::Truncated { at })?;
+                stack.push(u32::from_le_bytes(imm.try_into().expect("four bytes")));
+                pc += 4;
+            }
+            0x02 => {
+                pop(&mut stack)?;
+            }
+            0x03 => {
+                let a = pop(&mut stack)?;
+                stack.extend([a, a]);
+            }
+            0x04 => {
+                let b = pop(&mut stack)?;
+                let a = pop(&mut stack)?;
+                stack.extend([b, a]);
+            }
+            0x05 => {
+                let c = pop(&mut stack)?;
+                let b = pop(&mut stack)?;
+                let a = pop(&mut stack)?;
+                stack.extend([b, c, a]);
+            }
+            0x06 => stack.push(input.len() as u32),
+            0x07 => {
+                let i = pop(&mut stack)? as usize;
+                stack.push(input.get(i).copied().unwrap_or(0) as u32);
+            }
+            0x08..=0x10 => {
+                let b = pop(&mut stack)?;
+                let a = pop(&mut stack)?;
+                stack.push(match op {
+                    0x08 => a.wrapping_add(b),
+                    0x09 => a.wrapping_sub(b),
+                    0x0A => a.wrapping_mul(b),
+                    0x0B => a ^ b,
+                    0x0C => a & b,
+                    0x0D => a | b,
+                    0x0E => a.rotate_left(b),
+                    0x0F => (a == b) as u32,
+                    _ => mix(a, b as u8),
+                });
+            }
+            0x11 | 0x12 => {
+                let imm = program.get(pc..pc + 2).ok_or(VmError::Truncated { at })?;
+                let target = u16::from_le_bytes(imm.try_into().expect("two bytes")) as usize;
+                pc += 2;
+                if op == 0x11 || pop(&mut stack)? == 0 {
+                    pc = target;
+                }
+            }
+            _ => return Err(VmError::UnknownOpcode { at, op }),
+        }
+        if stack.len() > MAX_STACK {
+            return Err(VmError::StackOverflow { at });
+        }
+    }
+    Err(VmError::StepLimit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(value: u32) -> Vec<u8> {
+        let mut op = vec![0x01];
+        op.extend(value.to_le_bytes());
+        op
+    }
+
+    /// Runs `ops` followed by `HALT` on an empty input.
+    fn stack_after(ops: &[&[u8]]) -> Result<Vec<u32>, VmError> {
+        let mut program = ops.concat();
+        program.push(0x00);
+        execute(&program, b"")
+    }
+
+    #[test]
+    fn stack_opcodes() {
+        assert_eq!(stack_after(&[&push(7)]), Ok(vec![7]));
+        assert_eq!(stack_after(&[&push(1), &push(2), &[0x02]]), Ok(vec![1]));
+        assert_eq!(stack_after(&[&push(3), &[0x03]]), Ok(vec![3, 3]));
+        assert_eq!(stack_after(&[&push(1), &push(2), &[0x04]]), Ok(vec![2, 1]));
+        assert_eq!(stack_after(&[&push(1), &push(2), &push(3), &[0x05]]), Ok(vec![2, 3, 1]));
+    }
+
+    #[test]
+    fn arithmetic_opcodes() {
+        let binary = |op: u8, a: u32, b: u32| stack_after(&[&push(a), &push(b), &[op]]).unwrap()[0];
+        assert_eq!(binary(0x08, u32::MAX, 2), 1);
+        assert_eq!(binary(0x09, 1, 2), u32::MAX);
+        assert_eq!(binary(0x0A, 0x8000_0000, 2), 0);
+        assert_eq!(binary(0x0B, 0b1100, 0b1010), 0b0110);
+        assert_eq!(binary(0x0C, 0b1100, 0b1010), 0b1000);
+        assert_eq!(binary(0x0D, 0b1100, 0b1010), 0b1110);
+        assert_eq!(binary(0x0E, 0x8000_0001, 1), 3);
+        assert_eq!(binary(0x0F, 5, 5), 1);
+        assert_eq!(binary(0x0F, 5, 6), 0);
+        assert_eq!(binary(0x10, 0x1234_5678, b'Q' as u32), mix(0x1234_5678, b'Q'));
+    }
+
+    #[test]
+    fn input_opcodes() {
+        let program = [&[0x06][..], &push(1), &[0x07], &push(9), &[0x07, 0x00]].concat();
+        assert_eq!(execute(&program, b"abc"), Ok(vec![3, b'b' as u32, 0]));
+    }
+
+    #[test]
+    fn jumps() {
+        // 0: PUSH 0; 5: JZ 14; 8: PUSH 1; 13: HALT; 14: PUSH 2; 19: JMP 13
+        let mut program = push(0);
+        program.extend([0x12, 14, 0]);
+        program.extend(push(1));
+        program.push(0x00);
+        program.extend(push(2));
+        program.extend([0x11, 13, 0]);
+        assert_eq!(execute(&program, b""), Ok(vec![2]));
+        assert_eq!(run(&program, b""), Ok(true));
+    }
+
+    #[test]
+    fn accepts_on_a_non_zero_top() {
+        assert_eq!(run(&[[0x01].as_slice(), &[0; 4], &[0x00]].concat(), b""), Ok(false));
+        assert_eq!(run(&[0x00], b""), Ok(false));
+        assert_eq!(run(&[push(5), vec![0x00]].concat(), b""), Ok(true));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(execute(&[0xFF], b""), Err(VmError::UnknownOpcode { at: 0, op: 0xFF }));
+        assert_eq!(execute(&[0x01, 0, 0], b""), Err(VmError::Truncated { at: 0 }));
+        assert_eq!(execute(&[0x11, 0], b""), Err(VmError::Truncated { at: 0 }));
+        assert_eq!(execute(&push(1), b""), Err(VmError::Truncated { at: 5 }));
+        assert_eq!(execute(&[0x08], b""), Err(VmError::StackUnderflow { at: 0 }));
+        assert_eq!(execute(&[0x11, 0, 0], b""), Err(VmError::StepLimit));
+        let overflow = [push(1), vec![0x03, 0x11, 5, 0]].concat();
+        assert_eq!(execute(&overflow, b""), Err(VmError::StackOverflow { at: 5 }));
+    }
+}
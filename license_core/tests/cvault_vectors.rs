@@ -0,0 +1,78 @@
+//! Checks `license_core::vault` against the published `.cvault` test vectors
+//! in `docs/cvault-vectors.json`.
+
+use license_core::vault::{self, HEADER_LEN, Header, KdfParams};
+use serde::Deserialize;
+
+const VECTORS: &str = include_str!("../../docs/cvault-vectors.json");
+
+#[derive(Deserialize)]
+struct Vectors {
+    valid: Vec<Valid>,
+    invalid: Vec<Invalid>,
+}
+
+#[derive(Deserialize)]
+struct Valid {
+    name: String,
+    passphrase: String,
+    memory: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+    nonce_prefix: String,
+    chunk_size: u32,
+    key: String,
+    plaintext: String,
+    file: String,
+}
+
+#[derive(Deserialize)]
+struct Invalid {
+    name: String,
+    passphrase: String,
+    file: String,
+    error: String,
+}
+
+fn vectors() -> Vectors {
+    serde_json::from_str(VECTORS).expect("cvault-vectors.json parses")
+}
+
+#[test]
+fn valid_vectors_round_trip() {
+    for vector in vectors().valid {
+        let name = &vector.name;
+        let file = hex::decode(&vector.file).unwrap();
+        let plaintext = hex::decode(&vector.plaintext).unwrap();
+
+        let header = Header::parse(file[..HEADER_LEN].try_into().unwrap()).unwrap();
+        let expected = Header {
+            kdf: KdfParams { memory: vector.memory, iterations: vector.iterations, parallelism: vector.parallelism },
+            salt: hex::decode(&vector.salt).unwrap().try_into().unwrap(),
+            nonce_prefix: hex::decode(&vector.nonce_prefix).unwrap().try_into().unwrap(),
+            chunk_size: vector.chunk_size,
+        };
+        assert_eq!(header, expected, "{}: header", name);
+        assert_eq!(hex::encode(header.derive_key(&vector.passphrase).unwrap()), vector.key, "{}: key", name);
+
+        let mut decrypted = Vec::new();
+        vault::decrypt(&mut &file[..], &mut decrypted, &vector.passphrase, |_| {}).unwrap();
+        assert_eq!(decrypted, plaintext, "{}: decrypted", name);
+
+        let mut encrypted = Vec::new();
+        vault::encrypt(&mut &plaintext[..], &mut encrypted, &vector.passphrase, &header, |_| {}).unwrap();
+        assert_eq!(encrypted, file, "{}: encrypted", name);
+    }
+}
+
+#[test]
+fn invalid_vectors_are_rejected() {
+    for vector in vectors().invalid {
+        let file = hex::decode(&vector.file).unwrap();
+        match vault::decrypt(&mut &file[..], &mut Vec::new(), &vector.passphrase, |_| {}) {
+            Ok(_) => panic!("{}: accepted, expected {}", vector.name, vector.error),
+            Err(err) => assert_eq!(err.code(), vector.error, "{}: {}", vector.name, err),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Seals the challenge flag so that the plaintext never reaches the binary.
+//! Set `CYBERVAULT_FLAG` to build with a different flag. The decoy flag that
+//! replaces it under a debugger is sealed with the real flag, so it too only
+//! appears once a valid key has opened the flag.
+//!
+//! Medium and hard challenge tiers also strip symbols from the executable.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_FLAG: &str = "FLAG{cybervault_pro_license_cracked_successfully}";
+const DECOY_FLAG: &str = "FLAG{cybervault_pro_license_unlocked_at_runtime}";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=CYBERVAULT_FLAG");
+    let flag = env::var("CYBERVAULT_FLAG").unwrap_or_else(|_| DEFAULT_FLAG.to_string());
+    let sealed = license_core::seal_flag(license_core::KEY_TARGET, flag.as_bytes());
+    let out = PathBuf::from(env::var("OUT_DIR").expect("cargo sets OUT_DIR")).join("sealed_flag.rs");
+    let decoy = license_core::seal(flag.as_bytes(), DECOY_FLAG.as_bytes());
+    fs::write(
+        &out,
+        format!(
+            "const SEALED_FLAG: [u8; {}] = {:?};\nconst SEALED_DECOY: [u8; {}] = {:?};\n",
+            sealed.len(),
+            sealed,
+            decoy.len(),
+            decoy
+        ),
+    )
+    .expect("cannot write sealed flag");
+
+    let linux = env::var("CARGO_CFG_TARGET_OS").is_ok_and(|os| os == "linux");
+    if linux && env::var_os("CARGO_FEATURE_MEDIUM").is_some() {
+        println!("cargo:rustc-link-arg-bins=-s");
+    }
+}
@@ -0,0 +1,146 @@
+//! Client for the online activation and floating-license services
+//! (`license_core::protocol`).
+
+use std::fmt;
+use std::time::Duration;
+
+use license_core::protocol::{
+    ActivateRequest, ActivateResponse, DEFAULT_ACTIVATION_URL, DeactivateRequest, DeactivateResponse,
+    ErrorResponse, HeartbeatRequest, HeartbeatResponse, LeaseRequest, LeaseResponse, ReleaseLeaseRequest, ReleaseLeaseResponse,
+    RenewLeaseRequest, RenewLeaseResponse, RevocationListRequest, RevocationListResponse,
+    RevocationRequest, RevocationResponse,
+};
+use license_core::SignedRevocationList;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+pub enum ClientError {
+    /// The server could not be reached. Callers fall back to offline checks.
+    Unreachable(String),
+    /// The server answered with an error message.
+    Rejected(String),
+    /// Every floating seat is taken.
+    NoSeat(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Unreachable(reason) => write!(f, "activation server unreachable ({})", reason),
+            ClientError::Rejected(message) | ClientError::NoSeat(message) => f.write_str(message),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Client {
+    base_url: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    /// Uses `CYBERVAULT_ACTIVATION_URL`, or the local reference server.
+    pub fn from_env() -> Self {
+        let base_url = std::env::var("CYBERVAULT_ACTIVATION_URL")
+            .unwrap_or_else(|_| DEFAULT_ACTIVATION_URL.to_string());
+        Self::new(&base_url)
+    }
+
+    /// The floating-license server from `CYBERVAULT_LEASE_URL`, if floating
+    /// mode is configured.
+    pub fn lease_server_from_env() -> Option<Self> {
+        std::env::var("CYBERVAULT_LEASE_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .map(|url| Self::new(url.trim()))
+    }
+
+    fn new(base_url: &str) -> Self {
+        let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(10)).build();
+        Self { base_url: base_url.trim_end_matches('/').to_string(), agent }
+    }
+
+    pub fn activate(&self, key: &str, machine: &str) -> Result<ActivateResponse, ClientError> {
+        self.post("/v1/activate", &ActivateRequest {
+            key: key.to_string(),
+            machine: machine.to_string(),
+            app_version: crate::APP_VERSION.to_string(),
+        })
+    }
+
+    /// Releases the seat held by `activation_id`.
+    pub fn deactivate(&self, activation_id: &str, machine: &str) -> Result<DeactivateResponse, ClientError> {
+        self.post("/v1/deactivate", &DeactivateRequest {
+            activation_id: activation_id.to_string(),
+            machine: machine.to_string(),
+        })
+    }
+
+    pub fn heartbeat(&self, activation_id: &str, machine: &str) -> Result<HeartbeatResponse, ClientError> {
+        self.post("/v1/heartbeat", &HeartbeatRequest {
+            activation_id: activation_id.to_string(),
+            machine: machine.to_string(),
+        })
+    }
+
+    pub fn is_revoked(&self, license_id: &str) -> Result<bool, ClientError> {
+        let response: RevocationResponse = self.post("/v1/revocation", &RevocationRequest {
+            license_id: license_id.to_string(),
+        })?;
+        Ok(response.revoked)
+    }
+
+    /// The server's revocation list, if it is newer than `since_serial`.
+    pub fn fetch_revocations(&self, since_serial: u64) -> Result<Option<SignedRevocationList>, ClientError> {
+        let response: RevocationListResponse =
+            self.post("/v1/revocations", &RevocationListRequest { since_serial })?;
+        Ok(response.list)
+    }
+
+    pub fn acquire_lease(&self, machine: &str) -> Result<LeaseResponse, ClientError> {
+        self.post("/v1/lease/acquire", &LeaseRequest {
+            machine: machine.to_string(),
+            app_version: crate::APP_VERSION.to_string(),
+        })
+    }
+
+    pub fn renew_lease(&self, lease_id: &str, machine: &str) -> Result<RenewLeaseResponse, ClientError> {
+        self.post("/v1/lease/renew", &RenewLeaseRequest {
+            lease_id: lease_id.to_string(),
+            machine: machine.to_string(),
+        })
+    }
+
+    pub fn release_lease(&self, lease_id: &str, machine: &str) -> Result<ReleaseLeaseResponse, ClientError> {
+        self.post("/v1/lease/release", &ReleaseLeaseRequest {
+            lease_id: lease_id.to_string(),
+            machine: machine.to_string(),
+        })
+    }
+
+    fn post<Req: Serialize, Resp: DeserializeOwned>(&self, path: &str, body: &Req) -> Result<Resp, ClientError> {
+        let url = format!("{}{}", self.base_url, path);
+        tracing::debug!(%url, "request");
+        let result = match self.agent.post(&url).send_json(body) {
+            Ok(response) => response
+                .into_json()
+                .map_err(|e| ClientError::Rejected(format!("invalid server response: {}", e))),
+            Err(ureq::Error::Status(status, response)) => {
+                let message = response
+                    .into_json::<ErrorResponse>()
+                    .map(|e| e.error)
+                    .unwrap_or_else(|_| format!("server returned HTTP {}", status));
+                if status == 409 {
+                    Err(ClientError::NoSeat(message))
+                } else {
+                    Err(ClientError::Rejected(message))
+                }
+            }
+            Err(ureq::Error::Transport(err)) => Err(ClientError::Unreachable(err.to_string())),
+        };
+        if let Err(err) = &result {
+            tracing::warn!(%url, %err, "request failed");
+        }
+        result
+    }
+}
@@ -0,0 +1,200 @@
+//! Challenge pack progress.
+//!
+//! The built-in pack ships inside the binary; another pack can be opened from
+//! a `.cvpack` file and is reopened on the next launch. Solved levels are kept
+//! per pack name in the sealed store, so edited progress is discarded, along
+//! with the rewards they opened: a sealed reward needs the answer, which is
+//! not kept.
+//!
+//! At events, a challenge config (`challenge.json` next to the executable, or
+//! the file named by `CYBERVAULT_CHALLENGE_CONFIG`) switches the dashboard
+//! flag to a per-player one and supplies the hints. The number of hints
+//! revealed is kept per event in the sealed store and mirrored to the data
+//! store, like the trial date.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use license_core::{ChallengeConfig, ChallengePack, Level, Reward};
+use serde::{Deserialize, Serialize};
+
+use crate::store::{Store, StoreError};
+
+const PROGRESS_FILE: &str = "challenges.json";
+const BUILTIN_PACK: &str = include_str!("../../packs/cybervault.cvpack");
+const PLAYER_FILE: &str = "player.json";
+const HINTS_FILE: &str = "hints.json";
+const HINTS_MARKER: &str = ".cv-hints";
+const CONFIG_FILE: &str = "challenge.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct Progress {
+    /// Pack opened from disk, or `None` for the built-in one.
+    pack_path: Option<PathBuf>,
+    /// Solved level ids by pack name.
+    solved: BTreeMap<String, BTreeSet<String>>,
+    /// Opened rewards by pack name and level id.
+    #[serde(default)]
+    rewards: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+pub struct Challenges {
+    pub pack: ChallengePack,
+    progress: Progress,
+}
+
+impl Challenges {
+    /// Restores the last opened pack, falling back to the built-in one if it
+    /// can no longer be read.
+    pub fn load(store: Option<&Store>) -> Self {
+        let progress: Progress = store.and_then(|store| store.load(PROGRESS_FILE).ok().flatten()).unwrap_or_default();
+        let mut challenges = Self { pack: builtin(), progress };
+        if let Some(path) = challenges.progress.pack_path.clone() {
+            match read_pack(&path) {
+                Ok(pack) => challenges.pack = pack,
+                Err(_) => challenges.progress.pack_path = None,
+            }
+        }
+        challenges
+    }
+
+    pub fn open_pack(&mut self, path: &Path, store: Option<&Store>) -> Result<(), String> {
+        self.pack = read_pack(path)?;
+        self.progress.pack_path = Some(path.to_path_buf());
+        self.save(store);
+        Ok(())
+    }
+
+    pub fn use_builtin(&mut self, store: Option<&Store>) {
+        self.pack = builtin();
+        self.progress.pack_path = None;
+        self.save(store);
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        self.progress.pack_path.is_none()
+    }
+
+    pub fn is_solved(&self, level: &Level) -> bool {
+        self.progress.solved.get(&self.pack.name).is_some_and(|solved| solved.contains(&level.id))
+    }
+
+    /// The reward of a solved level, as opened when it was solved.
+    pub fn reward<'a>(&'a self, level: &'a Level) -> Option<&'a str> {
+        match &level.reward {
+            Reward::Plain(text) => Some(text),
+            Reward::Sealed { .. } => self.progress.rewards.get(&self.pack.name)?.get(&level.id).map(String::as_str),
+        }
+    }
+
+    pub fn solved_count(&self) -> usize {
+        self.pack.levels.iter().filter(|level| self.is_solved(level)).count()
+    }
+
+    /// Index of the level to play next. Levels unlock in order, so this is
+    /// the first unsolved one; `None` once the pack is complete.
+    pub fn current(&self) -> Option<usize> {
+        self.pack.levels.iter().position(|level| !self.is_solved(level))
+    }
+
+    /// Checks `answer` against the current level and records it as solved,
+    /// with its reward, if it passes.
+    pub fn submit(&mut self, answer: &str, store: Option<&Store>) -> Option<&Level> {
+        let index = self.current()?;
+        let level = &self.pack.levels[index];
+        if !level.check(answer) {
+            return None;
+        }
+        if let Reward::Sealed { .. } = level.reward {
+            let reward = level.open_reward(answer);
+            self.progress.rewards.entry(self.pack.name.clone()).or_default().insert(level.id.clone(), reward);
+        }
+        let id = level.id.clone();
+        self.progress.solved.entry(self.pack.name.clone()).or_default().insert(id);
+        self.save(store);
+        Some(&self.pack.levels[index])
+    }
+
+    /// Forgets the solved levels of the current pack.
+    pub fn reset(&mut self, store: Option<&Store>) {
+        self.progress.solved.remove(&self.pack.name);
+        self.progress.rewards.remove(&self.pack.name);
+        self.save(store);
+    }
+
+    fn save(&self, store: Option<&Store>) {
+        if let Some(store) = store {
+            let _ = store.save(PROGRESS_FILE, &self.progress);
+        }
+    }
+}
+
+fn builtin() -> ChallengePack {
+    ChallengePack::parse(BUILTIN_PACK).expect("built-in challenge pack is valid")
+}
+
+fn read_pack(path: &Path) -> Result<ChallengePack, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    ChallengePack::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Reads the event's challenge config, if one is installed.
+pub fn load_event_config() -> Result<Option<ChallengeConfig>, String> {
+    let path = match std::env::var_os("CYBERVAULT_CHALLENGE_CONFIG") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) else {
+                return Ok(None);
+            };
+            let path = dir.join(CONFIG_FILE);
+            if !path.exists() {
+                return Ok(None);
+            }
+            path
+        }
+    };
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    ChallengeConfig::parse(&text).map(Some).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn load_player_id(store: Option<&Store>) -> String {
+    store.and_then(|store| store.load(PLAYER_FILE).ok().flatten()).unwrap_or_default()
+}
+
+pub fn save_player_id(store: Option<&Store>, player_id: &str) {
+    if let Some(store) = store {
+        let _ = store.save(PLAYER_FILE, &player_id);
+    }
+}
+
+/// Hints revealed for `event`; the built-in hints are kept under `""`. The
+/// higher of the two copies counts, so deleting one does not lower it, and a
+/// copy that fails verification counts as every hint revealed.
+pub fn load_revealed_hints(store: Option<&Store>, event: &str) -> usize {
+    let data = Store::open_data();
+    [(store, HINTS_FILE), (data.as_ref(), HINTS_MARKER)]
+        .into_iter()
+        .map(|(store, name)| read_hints(store, name).map_or(usize::MAX, |revealed| revealed.get(event).copied().unwrap_or(0)))
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn save_revealed_hints(store: Option<&Store>, event: &str, count: usize) {
+    let data = Store::open_data();
+    for (store, name) in [(store, HINTS_FILE), (data.as_ref(), HINTS_MARKER)] {
+        let Some(store) = store else { continue };
+        let mut revealed = read_hints(Some(store), name).unwrap_or_default();
+        revealed.insert(event.to_string(), count);
+        let _ = store.save(name, &revealed);
+    }
+}
+
+/// The stored counts, or `None` if the file was modified.
+fn read_hints(store: Option<&Store>, name: &str) -> Option<BTreeMap<String, usize>> {
+    let Some(store) = store else { return Some(BTreeMap::new()) };
+    match store.load(name) {
+        Ok(revealed) => Some(revealed.unwrap_or_default()),
+        Err(StoreError::Io(_)) => Some(BTreeMap::new()),
+        Err(StoreError::Tampered) => None,
+    }
+}
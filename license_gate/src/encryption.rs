@@ -0,0 +1,226 @@
+//! File encryption behind the Encryption tab: background jobs that turn a
+//! file into `<file>.cvault` (see `license_core::vault`) and back, and report
+//! their progress.
+//!
+//! Output is written under a `.part` name and only renamed once every byte
+//! is on disk, so a failed run never leaves a truncated vault or a partly
+//! decrypted file behind. The original is only deleted after that.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{Local, NaiveDateTime};
+use license_core::vault::{self, HEADER_LEN, Header, TAG_LEN, VaultError};
+
+use crate::task::Task;
+
+pub const EXTENSION: &str = "cvault";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Clone, Copy)]
+pub struct Options {
+    pub delete_original: bool,
+    pub add_timestamp: bool,
+}
+
+pub struct Finished {
+    pub output: PathBuf,
+    /// What the job was started with; the UI may have changed since.
+    pub options: Options,
+    /// Set when the original was to be deleted but could not be.
+    pub delete_error: Option<String>,
+}
+
+pub struct Job {
+    pub source: PathBuf,
+    task: Task<Result<Finished, String>>,
+    done: Arc<AtomicU64>,
+    total: u64,
+}
+
+impl Job {
+    /// Checks that `source` can be read and the vault does not exist yet,
+    /// then starts encrypting in the background.
+    pub fn encrypt(source: PathBuf, passphrase: String, options: Options) -> Result<Self, String> {
+        let (mut file, len) = open_source(&source)?;
+        let output = vault_path(&source, options.add_timestamp);
+        Self::spawn(source, output, len, options, move |source, writer, done| {
+            vault::encrypt(&mut file, writer, &passphrase, &Header::generate(), |n| done.store(n, Ordering::Relaxed))
+                .map(drop)
+                .map_err(|err| vault_error("encrypt", source, err))
+        })
+    }
+
+    /// Checks that `source` can be read and the decrypted file does not
+    /// exist yet, then starts decrypting in the background. The vault is
+    /// kept.
+    pub fn decrypt(source: PathBuf, passphrase: String) -> Result<Self, String> {
+        let (mut file, len) = open_source(&source)?;
+        let output = plain_path(&source);
+        let total = plaintext_len(chunk_size(&mut file, &source)?, len);
+        let options = Options { delete_original: false, add_timestamp: false };
+        Self::spawn(source, output, total, options, move |source, writer, done| {
+            vault::decrypt(&mut file, writer, &passphrase, |n| done.store(n, Ordering::Relaxed))
+                .map(drop)
+                .map_err(|err| vault_error("decrypt", source, err))
+        })
+    }
+
+    /// Runs `write` against `<output>.part` on a background thread, renames
+    /// the result into place and then deletes `source` if `options` ask for it.
+    fn spawn<F>(source: PathBuf, output: PathBuf, total: u64, options: Options, write: F) -> Result<Self, String>
+    where
+        F: FnOnce(&Path, &mut BufWriter<File>, &AtomicU64) -> Result<(), String> + Send + 'static,
+    {
+        if output.exists() {
+            return Err(format!("Could not create {}: file already exists", output.display()));
+        }
+        let done = Arc::new(AtomicU64::new(0));
+        let progress = Arc::clone(&done);
+        let path = source.clone();
+        let task = Task::spawn(move || {
+            let mut name = output.file_name().unwrap_or_default().to_os_string();
+            name.push(".part");
+            let part = output.with_file_name(name);
+            let written = write_part(&part, |writer| write(&path, writer, &progress))
+                .and_then(|()| fs::rename(&part, &output).map_err(|e| describe("create", &output, &e)));
+            if let Err(err) = written {
+                let _ = fs::remove_file(&part);
+                return Err(err);
+            }
+            let delete_error = if options.delete_original {
+                fs::remove_file(&path).err().map(|e| describe("delete", &path, &e))
+            } else {
+                None
+            };
+            Ok(Finished { output, options, delete_error })
+        });
+        Ok(Self { source, task, done, total })
+    }
+
+    /// Fraction of the file processed so far.
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.done.load(Ordering::Relaxed) as f64 / self.total as f64).min(1.0) as f32
+    }
+
+    pub fn poll(&self) -> Option<Result<Finished, String>> {
+        let result = self.task.poll()?;
+        Some(result.unwrap_or_else(|err| Err(format!("Could not process {}: {}", self.source.display(), err))))
+    }
+}
+
+fn open_source(source: &Path) -> Result<(File, u64), String> {
+    let file = File::open(source).map_err(|e| describe("open", source, &e))?;
+    let metadata = file.metadata().map_err(|e| describe("read", source, &e))?;
+    if !metadata.is_file() {
+        return Err(format!("Could not open {}: not a file", source.display()));
+    }
+    Ok((file, metadata.len()))
+}
+
+/// Chunk size from the vault header, or the default if there is no valid
+/// header; the decryption then reports what is wrong with it. Leaves `file`
+/// at its start.
+fn chunk_size(file: &mut File, source: &Path) -> Result<u32, String> {
+    let mut header = [0u8; HEADER_LEN];
+    let chunk_size = match file.read_exact(&mut header) {
+        Ok(()) => Header::parse(&header).map_or(vault::DEFAULT_CHUNK_SIZE, |header| header.chunk_size),
+        Err(_) => vault::DEFAULT_CHUNK_SIZE,
+    };
+    file.seek(SeekFrom::Start(0)).map_err(|e| describe("read", source, &e))?;
+    Ok(chunk_size)
+}
+
+/// Plaintext bytes in a vault of `len` bytes: every chunk, including a
+/// short or empty last one, carries a [`TAG_LEN`] tag.
+fn plaintext_len(chunk_size: u32, len: u64) -> u64 {
+    let sealed = len.saturating_sub(HEADER_LEN as u64);
+    let chunks = sealed.div_ceil(chunk_size as u64 + TAG_LEN as u64);
+    sealed.saturating_sub(chunks * TAG_LEN as u64)
+}
+
+fn write_part<F>(part: &Path, write: F) -> Result<(), String>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<(), String>,
+{
+    let out = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(part)
+        .map_err(|e| describe("create", part, &e))?;
+    let mut writer = BufWriter::new(out);
+    write(&mut writer)?;
+    let out = writer.into_inner().map_err(|e| describe("write", part, e.error()))?;
+    out.sync_all().map_err(|e| describe("write", part, &e))
+}
+
+/// `<file>.cvault`, or `<file>.<date>-<time>.cvault` with a timestamp.
+fn vault_path(source: &Path, add_timestamp: bool) -> PathBuf {
+    let mut name = source.file_name().unwrap_or_default().to_os_string();
+    if add_timestamp {
+        name.push(".");
+        name.push(Local::now().format(TIMESTAMP_FORMAT).to_string());
+    }
+    name.push(".");
+    name.push(EXTENSION);
+    source.with_file_name(name)
+}
+
+/// The inverse of [`vault_path`]: `<file>` for `<file>[.<date>-<time>].cvault`,
+/// and `<file>.decrypted` for anything not named like a vault.
+fn plain_path(source: &Path) -> PathBuf {
+    let name = source.file_name().unwrap_or_default().to_string_lossy();
+    let Some(stem) = name.strip_suffix(&format!(".{}", EXTENSION)).filter(|stem| !stem.is_empty()) else {
+        return source.with_file_name(format!("{}.decrypted", name));
+    };
+    let stem = match stem.rsplit_once('.') {
+        Some((original, stamp))
+            if !original.is_empty() && NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).is_ok() =>
+        {
+            original
+        }
+        _ => stem,
+    };
+    source.with_file_name(stem)
+}
+
+fn vault_error(action: &str, source: &Path, err: VaultError) -> String {
+    match err {
+        VaultError::Io(e) => describe(action, source, &e),
+        err => format!("Could not {} {}: {}", action, source.display(), err),
+    }
+}
+
+fn describe(action: &str, path: &Path, err: &io::Error) -> String {
+    let reason = match err.kind() {
+        io::ErrorKind::NotFound => "file not found".to_string(),
+        io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+        io::ErrorKind::StorageFull => "disk full".to_string(),
+        io::ErrorKind::AlreadyExists => "file already exists".to_string(),
+        _ => err.to_string(),
+    };
+    format!("Could not {} {}: {}", action, path.display(), reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plaintext_len_matches_the_decrypted_length() {
+        let header = Header { chunk_size: 64, ..Header::generate() };
+        for size in [0, 1, 63, 64, 65, 128, 1000] {
+            let plaintext = vec![0x5A; size];
+            let mut sealed = Vec::new();
+            vault::encrypt(&mut &plaintext[..], &mut sealed, "passphrase", &header, |_| {}).unwrap();
+            let decrypted = vault::decrypt(&mut &sealed[..], &mut io::sink(), "passphrase", |_| {}).unwrap();
+            assert_eq!(plaintext_len(header.chunk_size, sealed.len() as u64), decrypted, "{} bytes", size);
+        }
+    }
+}
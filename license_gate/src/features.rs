@@ -0,0 +1,42 @@
+//! UI text for each licensable feature.
+
+use license_core::Feature;
+
+/// Label of the side panel button that opens the feature's tab.
+pub fn nav_label(feature: Feature) -> &'static str {
+    match feature {
+        Feature::Encryption => "🔒 File Encryption",
+        Feature::Passwords => "🔑 Password Manager",
+        Feature::Vault => "🗄 Secure Vault",
+        Feature::ActivityLog => "📋 Activity Log",
+        Feature::SecurityScan => "🛡️ Security Scan",
+        Feature::BackupRestore => "💾 Backup/Restore",
+        Feature::Analytics => "📊 Analytics",
+    }
+}
+
+/// Title and description shown on the dashboard "PREMIUM FEATURES" grid.
+pub fn card(feature: Feature) -> (&'static str, &'static str) {
+    match feature {
+        Feature::Encryption => ("🔒 Military-Grade Encryption", "AES-256 encryption for all your files"),
+        Feature::Passwords => ("🔑 Password Manager", "Securely store and manage unlimited passwords"),
+        Feature::Vault => ("🗄 Secure Vault", "Protected storage for sensitive documents"),
+        Feature::ActivityLog => ("📋 Activity Log", "Detailed record of every security event"),
+        Feature::SecurityScan => ("🛡️ Security Scan", "Scan files and passwords for vulnerabilities"),
+        Feature::BackupRestore => ("💾 Backup & Restore", "Scheduled backups of your encrypted data"),
+        Feature::Analytics => ("📊 Analytics", "Usage statistics and security reports"),
+    }
+}
+
+/// Line shown in the side panel when the feature is not licensed.
+pub fn limitation(feature: Feature) -> &'static str {
+    match feature {
+        Feature::Encryption => "No file encryption",
+        Feature::Passwords => "No password manager",
+        Feature::Vault => "No secure vault access",
+        Feature::ActivityLog => "No activity log",
+        Feature::SecurityScan => "No security scans",
+        Feature::BackupRestore => "No backup/restore",
+        Feature::Analytics => "No analytics",
+    }
+}
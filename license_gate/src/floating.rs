@@ -0,0 +1,166 @@
+//! Floating-license mode: holds a time-limited seat from the lease server and
+//! keeps it alive in the background.
+
+use std::time::{Duration, Instant};
+
+use license_core::SignedLicense;
+use license_core::protocol::{LeaseResponse, RenewLeaseResponse};
+
+use crate::activation::{Client, ClientError};
+use crate::task::Task;
+
+/// Wait before asking again after a seat was refused.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+/// Wait between renewals while the server cannot be reached.
+const RENEW_RETRY: Duration = Duration::from_secs(15);
+
+struct Lease {
+    id: String,
+    renew_at: Instant,
+    expires_at: Instant,
+}
+
+enum LeaseEvent {
+    Acquired(Result<LeaseResponse, ClientError>),
+    Renewed(Result<RenewLeaseResponse, ClientError>),
+}
+
+/// What the app has to act on after [`Floating::poll`].
+pub enum LeaseOutcome {
+    /// A seat was granted; the license is only valid while the lease is held.
+    Granted(SignedLicense),
+    /// The lease was renewed; its reissued license replaces the current one.
+    Renewed(SignedLicense),
+    /// The seat was lost and the license must be dropped.
+    Lost(String),
+}
+
+pub struct Floating {
+    client: Client,
+    machine: String,
+    lease: Option<Lease>,
+    task: Option<Task<LeaseEvent>>,
+    next_attempt: Instant,
+    /// Shown in the top bar while no seat is held.
+    pub message: Option<String>,
+}
+
+impl Floating {
+    /// `None` unless `CYBERVAULT_LEASE_URL` is set.
+    pub fn from_env(machine: String) -> Option<Self> {
+        Some(Self {
+            client: Client::lease_server_from_env()?,
+            machine,
+            lease: None,
+            task: None,
+            next_attempt: Instant::now(),
+            message: None,
+        })
+    }
+
+    pub fn has_lease(&self) -> bool {
+        self.lease.is_some()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.task.is_some() && self.lease.is_none()
+    }
+
+    /// Collects a finished request and starts the next acquire or renewal
+    /// once it is due. Called once per frame.
+    pub fn poll(&mut self) -> Option<LeaseOutcome> {
+        let now = Instant::now();
+        if let Some(task) = &self.task {
+            let event = task.poll()?;
+            self.task = None;
+            return match event {
+                Ok(event) => self.handle(event, now),
+                Err(err) => {
+                    tracing::warn!(%err, "lease request failed");
+                    self.next_attempt = now + RETRY_INTERVAL;
+                    None
+                }
+            };
+        }
+
+        let client = self.client.clone();
+        let machine = self.machine.clone();
+        match &self.lease {
+            Some(lease) if now >= lease.renew_at => {
+                let id = lease.id.clone();
+                self.task = Some(Task::spawn(move || LeaseEvent::Renewed(client.renew_lease(&id, &machine))));
+            }
+            None if now >= self.next_attempt => {
+                self.task = Some(Task::spawn(move || LeaseEvent::Acquired(client.acquire_lease(&machine))));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn handle(&mut self, event: LeaseEvent, now: Instant) -> Option<LeaseOutcome> {
+        match event {
+            LeaseEvent::Acquired(Ok(response)) => {
+                self.lease = Some(Lease {
+                    id: response.lease_id,
+                    renew_at: now + Duration::from_secs(response.lease_secs / 2),
+                    expires_at: now + Duration::from_secs(response.lease_secs),
+                });
+                self.message = None;
+                tracing::info!(lease_secs = response.lease_secs, "floating seat acquired");
+                Some(LeaseOutcome::Granted(response.license))
+            }
+            LeaseEvent::Acquired(Err(err)) => {
+                self.next_attempt = now + RETRY_INTERVAL;
+                self.message = Some(match err {
+                    ClientError::NoSeat(_) => "No floating seat available - running in trial mode".to_string(),
+                    ClientError::Unreachable(_) => "Lease server unreachable - running in trial mode".to_string(),
+                    ClientError::Rejected(message) => format!("{} - running in trial mode", message),
+                });
+                None
+            }
+            LeaseEvent::Renewed(Ok(response)) => {
+                tracing::debug!(lease_secs = response.lease_secs, "floating seat renewed");
+                if let Some(lease) = &mut self.lease {
+                    lease.renew_at = now + Duration::from_secs(response.lease_secs / 2);
+                    lease.expires_at = now + Duration::from_secs(response.lease_secs);
+                }
+                Some(LeaseOutcome::Renewed(response.license))
+            }
+            LeaseEvent::Renewed(Err(ClientError::Unreachable(_)))
+                if self.lease.as_ref().is_some_and(|lease| now < lease.expires_at) =>
+            {
+                // Keep the seat until the lease would have expired anyway.
+                tracing::warn!("lease server unreachable, keeping seat until the lease expires");
+                if let Some(lease) = &mut self.lease {
+                    lease.renew_at = now + RENEW_RETRY;
+                }
+                None
+            }
+            LeaseEvent::Renewed(Err(err)) => {
+                self.lease = None;
+                self.next_attempt = now + RETRY_INTERVAL;
+                let reason = match err {
+                    ClientError::Unreachable(_) => "Floating lease expired: lease server unreachable".to_string(),
+                    other => format!("Floating lease lost: {}", other),
+                };
+                self.message = Some(format!("{} - running in trial mode", reason));
+                Some(LeaseOutcome::Lost(reason))
+            }
+        }
+    }
+
+    /// Gives the seat back when its license could not be used.
+    pub fn reject(&mut self, reason: &str) {
+        self.release();
+        self.next_attempt = Instant::now() + RETRY_INTERVAL;
+        self.message = Some(format!("{} - running in trial mode", reason));
+    }
+
+    /// Hands the seat back to the pool. Blocks briefly; used on exit.
+    pub fn release(&mut self) {
+        if let Some(lease) = self.lease.take() {
+            let _ = self.client.release_lease(&lease.id, &self.machine);
+        }
+    }
+}
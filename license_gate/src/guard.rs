@@ -0,0 +1,64 @@
+//! Optional anti-debugging and integrity checks for advanced challenge
+//! rounds, Linux only.
+//!
+//! Each check is its own cargo feature, and `anti-debug` enables both:
+//!
+//! - `anti-debug-tracer`: a tracer is attached (`TracerPid` in
+//!   `/proc/self/status` is non-zero).
+//! - `anti-debug-text`: the `.text` section of `/proc/self/exe` no longer
+//!   matches the hash stamped into the binary by `stamp_text` after linking.
+//!   Unstamped builds skip this check.
+//!
+//! Without the features, on other platforms and in test builds, [`check`]
+//! finds nothing. A detection is not reported to the player; the app shows
+//! [`decoy_flag`] instead of the real flag.
+
+#[cfg(all(target_os = "linux", feature = "anti-debug-text"))]
+#[used]
+static TEXT_STAMP: [u8; license_core::TEXT_STAMP_LEN] = license_core::EMPTY_TEXT_STAMP;
+
+/// Runs every enabled check and describes the first one that fails.
+pub fn check() -> Option<String> {
+    if cfg!(test) {
+        return None;
+    }
+    #[cfg(all(target_os = "linux", feature = "anti-debug-tracer"))]
+    if let Some(pid) = tracer_pid().filter(|&pid| pid != 0) {
+        return Some(format!("tracer attached (pid {})", pid));
+    }
+    #[cfg(all(target_os = "linux", feature = "anti-debug-text"))]
+    if let Err(reason) = check_text() {
+        return Some(reason);
+    }
+    None
+}
+
+/// Shown on the dashboard in place of the real `flag` once a check failed.
+/// build.rs seals it with the real flag.
+pub fn decoy_flag(flag: &str) -> String {
+    String::from_utf8_lossy(&license_core::seal(flag.as_bytes(), &crate::SEALED_DECOY)).into_owned()
+}
+
+#[cfg(all(target_os = "linux", feature = "anti-debug-tracer"))]
+fn tracer_pid() -> Option<u32> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status.lines().find_map(|line| line.strip_prefix("TracerPid:")?.trim().parse().ok())
+}
+
+#[cfg(all(target_os = "linux", feature = "anti-debug-text"))]
+fn check_text() -> Result<(), String> {
+    // Read through an opaque reference so the compiler cannot fold the
+    // zeros it linked in.
+    let stamp = std::hint::black_box(&TEXT_STAMP);
+    let expected = &stamp[license_core::TEXT_STAMP_MAGIC.len()..];
+    if expected.iter().all(|&b| b == 0) {
+        tracing::debug!("text section not stamped, integrity check skipped");
+        return Ok(());
+    }
+    let exe = std::fs::read("/proc/self/exe").map_err(|e| format!("cannot read own executable: {}", e))?;
+    match license_core::text_section_hash(&exe) {
+        Some(hash) if hash[..] == *expected => Ok(()),
+        Some(_) => Err("text section modified".to_string()),
+        None => Err("text section not found".to_string()),
+    }
+}
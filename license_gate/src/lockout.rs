@@ -0,0 +1,138 @@
+//! Exponential cooldown between failed activation attempts.
+//!
+//! The failure count is kept in the sealed store so restarting the app does
+//! not reset it. A lockout file that fails verification is treated as a long
+//! run of failures rather than a clean slate.
+
+use serde::{Deserialize, Serialize};
+
+use crate::store::{Store, StoreError};
+
+const LOCKOUT_FILE: &str = "lockout.json";
+/// Failures allowed before any cooldown applies.
+const FREE_ATTEMPTS: u32 = 3;
+const BASE_DELAY_SECS: i64 = 5;
+const MAX_DELAY_SECS: i64 = 60 * 60;
+const TAMPERED_FAILURES: u32 = 10;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Lockout {
+    pub failures: u32,
+    /// Unix timestamp of the most recent failure.
+    last_failure: Option<i64>,
+}
+
+impl Lockout {
+    pub fn load(store: Option<&Store>, now: i64) -> Self {
+        let Some(store) = store else { return Self::default() };
+        match store.load(LOCKOUT_FILE) {
+            Ok(lockout) => lockout.unwrap_or_default(),
+            Err(StoreError::Io(_)) => Self::default(),
+            Err(StoreError::Tampered) => Self {
+                failures: TAMPERED_FAILURES,
+                last_failure: Some(now),
+            },
+        }
+    }
+
+    pub fn save(&self, store: Option<&Store>) {
+        if let Some(store) = store {
+            let _ = store.save(LOCKOUT_FILE, self);
+        }
+    }
+
+    /// Delay required after the current number of failures: none for the
+    /// first few, then 5s doubling per failure up to an hour.
+    pub fn cooldown_secs(&self) -> i64 {
+        if self.failures < FREE_ATTEMPTS {
+            return 0;
+        }
+        let doublings = (self.failures - FREE_ATTEMPTS).min(20);
+        (BASE_DELAY_SECS << doublings).min(MAX_DELAY_SECS)
+    }
+
+    /// Seconds until the next attempt is allowed.
+    pub fn remaining_secs(&self, now: i64) -> i64 {
+        let Some(last) = self.last_failure else { return 0 };
+        // A clock set backwards restarts the cooldown instead of skipping it
+        let elapsed = if now < last { 0 } else { now - last };
+        (self.cooldown_secs() - elapsed).max(0)
+    }
+
+    pub fn record_failure(&mut self, now: i64) {
+        self.failures = self.failures.saturating_add(1);
+        self.last_failure = Some(now);
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Formats a countdown as `m:ss`.
+pub fn format_wait(secs: i64) -> String {
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(failures: u32) -> Lockout {
+        let mut lockout = Lockout::default();
+        for _ in 0..failures {
+            lockout.record_failure(1_000);
+        }
+        lockout
+    }
+
+    #[test]
+    fn cooldown_doubles_after_the_free_attempts() {
+        let schedule: Vec<i64> = (0..12).map(|failures| after(failures).cooldown_secs()).collect();
+        assert_eq!(schedule, [0, 0, 0, 5, 10, 20, 40, 80, 160, 320, 640, 1280]);
+        assert_eq!(after(13).cooldown_secs(), MAX_DELAY_SECS);
+        assert_eq!(Lockout { failures: u32::MAX, last_failure: None }.cooldown_secs(), MAX_DELAY_SECS);
+    }
+
+    #[test]
+    fn remaining_counts_down_from_the_last_failure() {
+        let lockout = after(4);
+        assert_eq!(lockout.remaining_secs(1_000), 10);
+        assert_eq!(lockout.remaining_secs(1_007), 3);
+        assert_eq!(lockout.remaining_secs(1_010), 0);
+        assert_eq!(lockout.remaining_secs(5_000), 0);
+        // Setting the clock back does not skip the wait.
+        assert_eq!(lockout.remaining_secs(500), 10);
+        assert_eq!(Lockout::default().remaining_secs(1_000), 0);
+    }
+
+    #[test]
+    fn reset_clears_the_failures() {
+        let mut lockout = after(8);
+        lockout.reset();
+        assert_eq!(lockout.failures, 0);
+        assert_eq!(lockout.remaining_secs(1_000), 0);
+    }
+
+    #[test]
+    fn survives_a_restart_and_punishes_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::in_dir(dir.path().to_path_buf());
+        after(5).save(Some(&store));
+        let loaded = Lockout::load(Some(&store), 1_000);
+        assert_eq!(loaded.failures, 5);
+        assert_eq!(loaded.remaining_secs(1_000), 20);
+
+        std::fs::write(dir.path().join(LOCKOUT_FILE), r#"{ "data": { "failures": 0 }, "mac": "00" }"#).unwrap();
+        let tampered = Lockout::load(Some(&store), 2_000);
+        assert_eq!(tampered.failures, TAMPERED_FAILURES);
+        assert_eq!(tampered.remaining_secs(2_000), after(TAMPERED_FAILURES).cooldown_secs());
+    }
+
+    #[test]
+    fn formats_the_wait() {
+        assert_eq!(format_wait(0), "0:00");
+        assert_eq!(format_wait(65), "1:05");
+        assert_eq!(format_wait(MAX_DELAY_SECS), "60:00");
+    }
+}
@@ -0,0 +1,100 @@
+//! Diagnostics: leveled `tracing` output to stderr and to a daily rotating
+//! file in the data directory (`$XDG_DATA_HOME/cybervault-pro/logs` on Linux).
+//!
+//! The filter uses `EnvFilter` syntax and comes from `CYBERVAULT_LOG`, e.g.
+//! `CYBERVAULT_LOG=info,license_gate::activation=debug`. It can be changed at
+//! runtime from Settings. Product keys and passwords are logged through
+//! [`Secret`], which hides them unless `CYBERVAULT_LOG_SECRETS=1`.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use directories::ProjectDirs;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry, fmt as layer_fmt, reload};
+
+/// Levels offered in Settings, least verbose first.
+pub const LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+const DEFAULT_FILTER: &str = "info";
+/// Days of rotated log files kept.
+const MAX_LOG_FILES: usize = 7;
+
+pub struct Logging {
+    reload: reload::Handle<EnvFilter, Registry>,
+    /// Flushes the file sink when dropped.
+    _guard: Option<WorkerGuard>,
+    pub filter: String,
+    pub dir: Option<PathBuf>,
+}
+
+/// Installs the global subscriber. Logging to the file is skipped if the
+/// data directory cannot be created.
+pub fn init() -> Logging {
+    let filter = std::env::var("CYBERVAULT_LOG")
+        .ok()
+        .filter(|f| EnvFilter::try_new(f).is_ok())
+        .unwrap_or_else(|| DEFAULT_FILTER.to_string());
+    let (filter_layer, reload) = reload::Layer::new(EnvFilter::new(&filter));
+
+    let dir = ProjectDirs::from("com", "CyberVault", "CyberVault Pro").map(|dirs| dirs.data_local_dir().join("logs"));
+    let appender = dir.as_ref().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("cybervault")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .ok()
+    });
+    let (file_layer, guard) = match appender {
+        Some(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (Some(layer_fmt::layer().with_ansi(false).with_writer(writer)), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(layer_fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .init();
+    tracing::info!(version = crate::APP_VERSION, filter = %filter, "logging started");
+
+    let dir = dir.filter(|_| guard.is_some());
+    Logging { reload, _guard: guard, filter, dir }
+}
+
+impl Logging {
+    /// Replaces the active filter, e.g. with one of [`LEVELS`].
+    pub fn set_filter(&mut self, filter: &str) -> Result<(), String> {
+        let parsed = EnvFilter::try_new(filter).map_err(|e| e.to_string())?;
+        self.reload.reload(parsed).map_err(|e| e.to_string())?;
+        self.filter = filter.to_string();
+        tracing::info!(filter, "log filter changed");
+        Ok(())
+    }
+}
+
+/// A value that is only written to logs as its length, unless
+/// `CYBERVAULT_LOG_SECRETS=1`.
+pub struct Secret<'a>(pub &'a str);
+
+impl fmt::Display for Secret<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if reveal_secrets() {
+            f.write_str(self.0)
+        } else {
+            write!(f, "<redacted, {} chars>", self.0.chars().count())
+        }
+    }
+}
+
+fn reveal_secrets() -> bool {
+    static REVEAL: OnceLock<bool> = OnceLock::new();
+    *REVEAL.get_or_init(|| std::env::var("CYBERVAULT_LOG_SECRETS").is_ok_and(|v| v == "1"))
+}
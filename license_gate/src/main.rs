@@ -1,9 +1,55 @@
 use eframe::egui;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use chrono::Local;
+use license_core::{ChallengeConfig, Edition, Entitlements, Feature, GroupCheck, KeyFormat, License, LicenseError, MachineFingerprint, RevocationList, Scoring, SignedLicense, SignedRevocationList, Standing, Tier};
+
+mod activation;
+mod challenges;
+mod encryption;
+mod features;
+mod floating;
+mod guard;
+mod lockout;
+mod logging;
+mod store;
+mod strings;
+mod task;
+mod trial;
+
+use activation::{Client, ClientError};
+use challenges::Challenges;
+use encryption::Job;
+use license_core::protocol::{ActivateResponse, DeactivateResponse};
+use floating::{Floating, LeaseOutcome};
+use lockout::Lockout;
+use logging::{Logging, Secret};
+use store::{Store, StoreError, StoredLicense};
+use strings::text;
+use task::Task;
+use trial::Trial;
+
+const APP_VERSION: &str = "3.2.1";
+/// How often an online activation is confirmed with the server.
+const LICENSE_CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+// The challenge flag and the decoy as sealed by build.rs:
+// `const SEALED_FLAG: [u8; N]` and `const SEALED_DECOY: [u8; M]`.
+include!(concat!(env!("OUT_DIR"), "/sealed_flag.rs"));
+
+/// The key and format submitted for activation, and the server's answer.
+type ActivationAttempt = (String, KeyFormat, Result<ActivateResponse, ClientError>);
+
+/// Result of a background heartbeat or revocation check.
+enum LicenseCheck {
+    Valid,
+    Invalid(String),
+    Unreachable,
+}
 
 fn main() -> eframe::Result<()> {
+    let logging = logging::init();
     let options = eframe::NativeOptions {
         centered: true,
         viewport: egui::ViewportBuilder::default()
@@ -23,7 +69,7 @@ fn main() -> eframe::Result<()> {
             visuals.widgets.hovered.bg_fill = egui::Color32::from_rgb(60, 70, 85);
             visuals.widgets.active.bg_fill = egui::Color32::from_rgb(70, 120, 200);
             cc.egui_ctx.set_visuals(visuals);
-            Ok(Box::new(LicenseApp::default()))
+            Ok(Box::new(LicenseApp { logging: Some(logging), ..Default::default() }))
         }),
     )
 }
@@ -31,6 +77,7 @@ fn main() -> eframe::Result<()> {
 #[derive(PartialEq, Clone)]
 enum Tab {
     Dashboard,
+    Challenges,
     Encryption,
     Passwords,
     Vault,
@@ -41,17 +88,88 @@ enum Tab {
     Settings,
 }
 
+impl Tab {
+    /// The licensed feature behind this tab, or `None` if it is always available.
+    fn feature(&self) -> Option<Feature> {
+        match self {
+            Tab::Dashboard | Tab::Challenges | Tab::Settings => None,
+            Tab::Encryption => Some(Feature::Encryption),
+            Tab::Passwords => Some(Feature::Passwords),
+            Tab::Vault => Some(Feature::Vault),
+            Tab::ActivityLog => Some(Feature::ActivityLog),
+            Tab::SecurityScan => Some(Feature::SecurityScan),
+            Tab::BackupRestore => Some(Feature::BackupRestore),
+            Tab::Analytics => Some(Feature::Analytics),
+        }
+    }
+}
+
 struct LicenseApp {
     key_input: String,
+    /// Format picked in the activation window; `None` detects it from the input.
+    key_format: Option<KeyFormat>,
+    /// Shown under the key field after a character was rejected while typing.
+    key_warning: Option<String>,
     status: String,
-    tries: u32,
-    unlocked: bool,
+    lockout: Lockout,
+    license: Option<License>,
+    entitlements: Entitlements,
+    /// Where the current license is on its way from expiry to read-only.
+    standing: Standing,
+    /// The challenge flag, opened with the legacy key that unlocked the app.
+    flag: Option<String>,
+    /// The legacy key an online activation was made with. The server answers
+    /// with a signed license, so this is what opens the flag afterwards.
+    activation_key: Option<String>,
+    /// Why an anti-debugging check failed. From then on the dashboard shows
+    /// the decoy flag.
+    tampered: Option<String>,
+    /// Event config that turns the flag into a per-player one.
+    event: Option<ChallengeConfig>,
+    /// The event secret, opened the same way as the flag.
+    event_secret: Option<String>,
+    /// Opens the flag and event secret off the UI thread, since the key
+    /// stretch takes tens of milliseconds.
+    unseal: Option<Task<(String, Option<String>)>>,
+    player_id: String,
+    /// Hints and score of the event, or the built-in ones.
+    scoring: Scoring,
+    revealed_hints: usize,
+    store: Option<Store>,
+    trial: Trial,
+    machine: MachineFingerprint,
+    client: Client,
+    activation_id: Option<String>,
+    pending_activation: Option<Task<ActivationAttempt>>,
+    license_check: Option<Task<LicenseCheck>>,
+    next_license_check: Instant,
+    floating: Option<Floating>,
+    revocations: RevocationList,
+    revocation_refresh: Option<Task<Result<Option<SignedRevocationList>, ClientError>>>,
+    request_code: String,
+    offline_response: String,
+    /// Set while the Deactivate button waits for confirmation.
+    confirm_deactivation: bool,
+    pending_deactivation: Option<Task<Result<DeactivateResponse, ClientError>>>,
+    /// Shown after a deactivation the activation server did not record.
+    deactivation_receipt: Option<String>,
     show_activation: bool,
     current_tab: Tab,
+    // Challenge pack state
+    challenges: Challenges,
+    challenge_answer: String,
+    challenge_status: String,
     // Encryption feature state
     file_to_encrypt: String,
-    encryption_progress: f32,
-    encryption_running: bool,
+    encryption_passphrase: String,
+    encryption_confirm: String,
+    encryption: Option<Job>,
+    /// Outcome of the last run: what was written, or why it failed.
+    encryption_status: Option<Result<String, String>>,
+    file_to_decrypt: String,
+    decryption_passphrase: String,
+    decryption: Option<Job>,
+    decryption_status: Option<Result<String, String>>,
     // Password manager state
     passwords: HashMap<String, String>,
     password_visible: HashMap<String, bool>,
@@ -76,8 +194,8 @@ struct LicenseApp {
     notifications: bool,
     require_password: bool,
     two_factor: bool,
+    logging: Option<Logging>,
     // Encryption settings
-    use_aes256: bool,
     delete_original: bool,
     add_timestamp: bool,
     // Security scan settings
@@ -165,16 +283,62 @@ impl Default for LicenseApp {
             ("2026-01-30 07:00:00".to_string(), "Incremental".to_string(), "150 MB".to_string()),
         ];
 
-        Self {
+        let store = Store::open();
+        let machine = MachineFingerprint::collect();
+        let trial = Trial::load_or_start(store.as_ref(), Store::open_data().as_ref(), Local::now().date_naive());
+        let lockout = Lockout::load(store.as_ref(), Local::now().timestamp());
+        let challenges = Challenges::load(store.as_ref());
+        let player_id = challenges::load_player_id(store.as_ref());
+        let event = challenges::load_event_config();
+
+        let mut app = Self {
             key_input: String::new(),
+            key_format: None,
+            key_warning: None,
             status: String::new(),
-            tries: 0,
-            unlocked: false,
+            lockout,
+            license: None,
+            entitlements: Entitlements::none(),
+            standing: Standing::Active,
+            flag: None,
+            activation_key: None,
+            tampered: None,
+            event: None,
+            event_secret: None,
+            unseal: None,
+            player_id,
+            scoring: Scoring::default(),
+            revealed_hints: 0,
+            store,
+            trial,
+            request_code: license_core::request_code(&machine),
+            machine,
+            client: Client::from_env(),
+            activation_id: None,
+            pending_activation: None,
+            license_check: None,
+            next_license_check: Instant::now(),
+            floating: None,
+            revocations: RevocationList::default(),
+            revocation_refresh: None,
+            offline_response: String::new(),
+            confirm_deactivation: false,
+            pending_deactivation: None,
+            deactivation_receipt: None,
             show_activation: true,
             current_tab: Tab::Dashboard,
+            challenges,
+            challenge_answer: String::new(),
+            challenge_status: String::new(),
             file_to_encrypt: String::new(),
-            encryption_progress: 0.0,
-            encryption_running: false,
+            encryption_passphrase: String::new(),
+            encryption_confirm: String::new(),
+            encryption: None,
+            encryption_status: None,
+            file_to_decrypt: String::new(),
+            decryption_passphrase: String::new(),
+            decryption: None,
+            decryption_status: None,
             passwords,
             password_visible: HashMap::new(),
             new_site: String::new(),
@@ -192,7 +356,7 @@ impl Default for LicenseApp {
             notifications: true,
             require_password: true,
             two_factor: true,
-            use_aes256: true,
+            logging: None,
             delete_original: true,
             add_timestamp: false,
             scan_encrypted: true,
@@ -201,14 +365,53 @@ impl Default for LicenseApp {
             deep_scan: false,
             clipboard_message: None,
             clipboard_timer: 0.0,
+        };
+        if app.trial.reset_detected {
+            app.add_activity_log("Trial reset attempt detected".to_string(),
+                "Blocked".to_string(), "⚠".to_string());
+        }
+        match event {
+            Ok(Some(config)) => {
+                app.add_activity_log(format!("Challenge config loaded: {}", config.event),
+                    "Event".to_string(), "🏁".to_string());
+                app.scoring = config.scoring.clone();
+                app.event = Some(config);
+            }
+            Ok(None) => {}
+            Err(err) => app.add_activity_log(format!("Challenge config ignored: {}", err),
+                "Error".to_string(), "⚠".to_string()),
+        }
+        app.revealed_hints =
+            challenges::load_revealed_hints(app.store.as_ref(), app.event_name()).min(app.scoring.hints.len());
+        app.run_guard();
+        app.load_revocations();
+        app.restore_license();
+        let client = app.client.clone();
+        let serial = app.revocations.serial;
+        app.revocation_refresh = Some(Task::spawn(move || client.fetch_revocations(serial)));
+        if app.license.is_none() {
+            app.entitlements = app.trial_entitlements();
+            // A license of its own takes precedence over the floating pool.
+            app.floating = Floating::from_env(app.machine.machine_id());
+            if app.floating.is_some() {
+                app.show_activation = false;
+            }
         }
+        app
     }
 }
 
 impl eframe::App for LicenseApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Some(floating) = &mut self.floating {
+            floating.release();
+        }
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Update animations and timers
         self.update_animations(ctx);
+        self.poll_tasks(ctx);
         // Top bar
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.add_space(8.0);
@@ -220,16 +423,43 @@ impl eframe::App for LicenseApp {
                 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.add_space(16.0);
-                    if self.unlocked {
-                        ui.label(egui::RichText::new("✓ Licensed").color(egui::Color32::from_rgb(100, 255, 100)));
+                    let floating = self.floating.as_ref();
+                    if self.license.is_some() {
+                        match self.standing {
+                            Standing::Active => {
+                                let label = if floating.is_some_and(Floating::has_lease) { text!("✓ Licensed (floating seat)") } else { text!("✓ Licensed") };
+                                ui.label(egui::RichText::new(label).color(egui::Color32::from_rgb(100, 255, 100)));
+                            }
+                            Standing::Grace { until } => {
+                                let days = (until - Local::now().date_naive()).num_days() + 1;
+                                ui.label(egui::RichText::new(format!("⚠ License expired - {} days of grace remaining", days)).color(egui::Color32::from_rgb(255, 180, 0)));
+                            }
+                            Standing::ReadOnly { .. } => {
+                                ui.label(egui::RichText::new("⛔ License expired - read-only mode").color(egui::Color32::from_rgb(255, 100, 100)));
+                            }
+                        }
+                        if self.standing != Standing::Active && ui.button("🔓 Renew License").clicked() {
+                            self.show_activation = true;
+                        }
                         if ui.button("⚙ Settings").clicked() {
                             self.current_tab = Tab::Settings;
                         }
                     } else {
-                        ui.label(egui::RichText::new("⚠ Trial Mode").color(egui::Color32::from_rgb(255, 180, 0)));
+                        let days = self.trial.days_remaining(Local::now().date_naive());
+                        if days > 0 {
+                            ui.label(egui::RichText::new(format!("⚠ Trial Mode - {} days remaining", days)).color(egui::Color32::from_rgb(255, 180, 0)));
+                        } else {
+                            ui.label(egui::RichText::new("⛔ Trial Expired").color(egui::Color32::from_rgb(255, 100, 100)));
+                        }
                         if ui.button("🔓 Activate License").clicked() {
                             self.show_activation = true;
                         }
+                        if floating.is_some_and(Floating::is_pending) {
+                            ui.spinner();
+                            ui.label("Requesting floating seat...");
+                        } else if let Some(message) = floating.and_then(|f| f.message.as_deref()) {
+                            ui.label(egui::RichText::new(format!("🎫 {}", message)).color(egui::Color32::from_rgb(255, 180, 0)));
+                        }
                     }
                 });
             });
@@ -237,7 +467,7 @@ impl eframe::App for LicenseApp {
         });
 
         // License activation window
-        if self.show_activation && !self.unlocked {
+        if self.show_activation {
             egui::Window::new("License Activation")
                 .collapsible(false)
                 .resizable(false)
@@ -251,24 +481,67 @@ impl eframe::App for LicenseApp {
                     
                     ui.label("Enter your license key to unlock all premium features:");
                     ui.add_space(12.0);
-                    
+
+                    ui.horizontal(|ui| {
+                        ui.label("Key Format:");
+                        let selected = match self.key_format {
+                            Some(format) => format.to_string(),
+                            None => "Auto-detect".to_string(),
+                        };
+                        egui::ComboBox::from_id_salt("key_format")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.key_format, None, "Auto-detect");
+                                for format in KeyFormat::ALL {
+                                    ui.selectable_value(&mut self.key_format, Some(format), format.to_string());
+                                }
+                            });
+                    });
+                    let format = self.current_key_format();
+                    ui.add_space(4.0);
+
                     ui.horizontal(|ui| {
                         ui.label("License Key:");
                         let response = ui.add_sized(
                             [250.0, 20.0],
                             egui::TextEdit::singleline(&mut self.key_input)
-                                .hint_text("XXXX-XXXX-XXXX")
+                                .hint_text(format.pattern())
                                 .password(false)
                         );
+                        if response.changed() && self.normalize_key_input() {
+                            // Keep the cursor at the end after dashes were inserted
+                            if let Some(mut state) = egui::TextEdit::load_state(ui.ctx(), response.id) {
+                                let end = egui::text::CCursor::new(self.key_input.chars().count());
+                                state.cursor.set_char_range(Some(egui::text::CCursorRange::one(end)));
+                                state.store(ui.ctx(), response.id);
+                            }
+                        }
                         if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                             self.attempt_unlock();
                         }
                     });
 
+                    let checks = format.check_groups(&self.key_input);
+                    if !self.key_input.is_empty() && !checks.is_empty() {
+                        ui.horizontal(|ui| {
+                            for (i, check) in checks.iter().enumerate() {
+                                let (icon, color) = match check {
+                                    GroupCheck::Valid => ("✓", egui::Color32::from_rgb(100, 255, 100)),
+                                    GroupCheck::Invalid => ("✗", egui::Color32::from_rgb(255, 100, 100)),
+                                    GroupCheck::Incomplete => ("…", egui::Color32::GRAY),
+                                };
+                                ui.colored_label(color, format!("{} Group {}", icon, i + 1));
+                            }
+                        });
+                    }
+                    if let Some(warning) = &self.key_warning {
+                        ui.colored_label(egui::Color32::from_rgb(255, 180, 0), warning);
+                    }
+
                     ui.add_space(8.0);
 
                     if !self.status.is_empty() {
-                        let color = if self.unlocked {
+                        let color = if self.license.is_some() {
                             egui::Color32::from_rgb(100, 255, 100)
                         } else {
                             egui::Color32::from_rgb(255, 100, 100)
@@ -276,8 +549,23 @@ impl eframe::App for LicenseApp {
                         ui.colored_label(color, &self.status);
                     }
 
-                    if self.tries > 0 {
-                        ui.label(format!("Activation attempts: {}", self.tries));
+                    if self.lockout.failures > 0 {
+                        ui.label(format!("Failed activation attempts: {}", self.lockout.failures));
+                    }
+
+                    let activating = self.pending_activation.is_some();
+                    if activating {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Contacting activation server...");
+                        });
+                    }
+
+                    let wait = self.lockout.remaining_secs(Local::now().timestamp());
+                    if wait > 0 {
+                        ui.colored_label(egui::Color32::from_rgb(255, 180, 0),
+                            format!("⏳ Too many failed attempts. Try again in {}", lockout::format_wait(wait)));
+                        ctx.request_repaint_after(std::time::Duration::from_secs(1));
                     }
 
                     ui.add_space(12.0);
@@ -285,10 +573,14 @@ impl eframe::App for LicenseApp {
                     ui.add_space(8.0);
 
                     ui.horizontal(|ui| {
-                        if ui.button(egui::RichText::new("🔓 Activate").size(14.0)).clicked() {
+                        if ui.add_enabled(wait == 0 && !activating, egui::Button::new(egui::RichText::new("🔓 Activate").size(14.0))).clicked() {
                             self.attempt_unlock();
                         }
 
+                        if ui.button("📄 Load License File").clicked() {
+                            self.load_license_file();
+                        }
+
                         if ui.button("Clear").clicked() {
                             self.key_input.clear();
                             self.status.clear();
@@ -301,11 +593,58 @@ impl eframe::App for LicenseApp {
                         });
                     });
 
+                    ui.add_space(8.0);
+                    let mut copy_request = false;
+                    let mut apply_response = false;
+                    egui::CollapsingHeader::new("🖧 Offline activation").show(ui, |ui| {
+                        ui.small("No internet access? Send this request code to the CyberVault activation portal:");
+                        ui.horizontal(|ui| {
+                            ui.code(&self.request_code);
+                            if ui.small_button("📋").clicked() {
+                                copy_request = true;
+                            }
+                        });
+                        ui.add_space(6.0);
+                        ui.label("Response code:");
+                        ui.add(egui::TextEdit::multiline(&mut self.offline_response)
+                            .desired_rows(3)
+                            .desired_width(380.0));
+                        if ui.add_enabled(!self.offline_response.trim().is_empty(), egui::Button::new("✔ Apply Response Code")).clicked() {
+                            apply_response = true;
+                        }
+                    });
+                    if copy_request {
+                        let code = self.request_code.clone();
+                        self.copy_to_clipboard(&code);
+                    }
+                    if apply_response {
+                        self.apply_offline_response();
+                    }
+
+                    let mut reveal_hint = false;
+                    let score = self.scoring.score(self.revealed_hints);
+                    egui::CollapsingHeader::new(format!("💡 Hints ({}/{})", self.revealed_hints, self.scoring.hints.len()))
+                        .show(ui, |ui| {
+                            for (i, hint) in self.scoring.hints.iter().take(self.revealed_hints).enumerate() {
+                                ui.label(format!("{}. {}", i + 1, hint.text));
+                            }
+                            if let Some(hint) = self.scoring.hints.get(self.revealed_hints) {
+                                let label = format!("Reveal hint {} (−{} points)", self.revealed_hints + 1, hint.penalty);
+                                if ui.button(label).clicked() {
+                                    reveal_hint = true;
+                                }
+                            }
+                            ui.small(format!("Score: {} / {}", score, self.scoring.max_score));
+                        });
+                    if reveal_hint {
+                        self.reveal_hint();
+                    }
+
                     ui.add_space(10.0);
                     ui.separator();
                     ui.add_space(6.0);
-                    ui.small("Note: This is a reverse engineering challenge.");
-                    ui.small("Format: XXXX-XXXX-XXXX (14 characters including dashes)");
+                    ui.small(text!("Note: This is a reverse engineering challenge."));
+                    ui.small(format!("Format: {} ({})", format.pattern(), format.description()));
                 });
         }
 
@@ -319,66 +658,61 @@ impl eframe::App for LicenseApp {
             if ui.selectable_label(dashboard_selected, "📊 Dashboard").clicked() {
                 self.current_tab = Tab::Dashboard;
             }
-
-            let encryption_selected = self.current_tab == Tab::Encryption;
-            if ui.add_enabled(self.unlocked, egui::Button::new("🔒 File Encryption").selected(encryption_selected)).clicked() {
-                self.current_tab = Tab::Encryption;
+            if ui.selectable_label(self.current_tab == Tab::Challenges, "🏁 Challenges").clicked() {
+                self.current_tab = Tab::Challenges;
             }
 
-            let passwords_selected = self.current_tab == Tab::Passwords;
-            if ui.add_enabled(self.unlocked, egui::Button::new("🔑 Password Manager").selected(passwords_selected)).clicked() {
-                self.current_tab = Tab::Passwords;
-            }
-
-            let vault_selected = self.current_tab == Tab::Vault;
-            if ui.add_enabled(self.unlocked, egui::Button::new("🗄 Secure Vault").selected(vault_selected)).clicked() {
-                self.current_tab = Tab::Vault;
-            }
+            self.nav_button(ui, Tab::Encryption);
+            self.nav_button(ui, Tab::Passwords);
+            self.nav_button(ui, Tab::Vault);
 
             ui.add_space(10.0);
             ui.separator();
             ui.add_space(10.0);
             ui.label(egui::RichText::new("ADVANCED").size(11.0).color(egui::Color32::GRAY));
 
-            let activity_selected = self.current_tab == Tab::ActivityLog;
-            if ui.add_enabled(self.unlocked, egui::Button::new("📋 Activity Log").selected(activity_selected)).clicked() {
-                self.current_tab = Tab::ActivityLog;
-            }
-
-            let security_selected = self.current_tab == Tab::SecurityScan;
-            if ui.add_enabled(self.unlocked, egui::Button::new("🛡️ Security Scan").selected(security_selected)).clicked() {
-                self.current_tab = Tab::SecurityScan;
-            }
-
-            let backup_selected = self.current_tab == Tab::BackupRestore;
-            if ui.add_enabled(self.unlocked, egui::Button::new("💾 Backup/Restore").selected(backup_selected)).clicked() {
-                self.current_tab = Tab::BackupRestore;
-            }
-
-            let analytics_selected = self.current_tab == Tab::Analytics;
-            if ui.add_enabled(self.unlocked, egui::Button::new("📊 Analytics").selected(analytics_selected)).clicked() {
-                self.current_tab = Tab::Analytics;
-            }
+            self.nav_button(ui, Tab::ActivityLog);
+            self.nav_button(ui, Tab::SecurityScan);
+            self.nav_button(ui, Tab::BackupRestore);
+            self.nav_button(ui, Tab::Analytics);
 
             ui.add_space(20.0);
             ui.separator();
             ui.add_space(10.0);
 
-            if !self.unlocked {
-                ui.label(egui::RichText::new("⚠ Trial Limitations:").color(egui::Color32::from_rgb(255, 180, 0)).strong());
+            let missing: Vec<Feature> = Feature::ALL
+                .into_iter()
+                .filter(|f| !self.entitlements.contains(*f))
+                .collect();
+            if self.entitlements.is_read_only() {
+                ui.label(egui::RichText::new("⛔ License Expired:").color(egui::Color32::from_rgb(255, 100, 100)).strong());
+                ui.add_space(6.0);
+                ui.small("• Read-only mode");
+                ui.small("• Vault and passwords can be viewed and exported");
+                ui.small("• Encrypting, adding and deleting are disabled");
+                ui.add_space(10.0);
+                if ui.button("🔓 Renew License").clicked() {
+                    self.show_activation = true;
+                }
+            } else if !missing.is_empty() {
+                let heading = match &self.license {
+                    Some(license) => format!("⚠ {} Edition Limitations:", license.edition()),
+                    None if self.trial.is_expired(Local::now().date_naive()) => "⛔ Trial Expired:".to_string(),
+                    None => "⚠ Trial Limitations:".to_string(),
+                };
+                ui.label(egui::RichText::new(heading).color(egui::Color32::from_rgb(255, 180, 0)).strong());
                 ui.add_space(6.0);
-                ui.small("• Limited file encryption");
-                ui.small("• No password manager");
-                ui.small("• Read-only vault access");
-                ui.small("• Watermarked exports");
+                for feature in missing {
+                    ui.small(format!("• {}", features::limitation(feature)));
+                }
                 ui.add_space(10.0);
                 if ui.button("🔓 Upgrade Now").clicked() {
                     self.show_activation = true;
                 }
             } else {
-                ui.label(egui::RichText::new("✓ Full Access").color(egui::Color32::from_rgb(100, 255, 100)).strong());
+                ui.label(egui::RichText::new(text!("✓ Full Access")).color(egui::Color32::from_rgb(100, 255, 100)).strong());
                 ui.add_space(6.0);
-                ui.small("All features unlocked");
+                ui.small(text!("All features unlocked"));
             }
         });
 
@@ -395,6 +729,7 @@ impl eframe::App for LicenseApp {
                 Tab::SecurityScan => self.show_security_scan(ui),
                 Tab::BackupRestore => self.show_backup_restore(ui),
                 Tab::Analytics => self.show_analytics(ui),
+                Tab::Challenges => self.show_challenges(ui),
                 Tab::Settings => self.show_settings(ui),
             }
         });
@@ -403,24 +738,24 @@ impl eframe::App for LicenseApp {
 
 impl LicenseApp {
     fn update_animations(&mut self, ctx: &egui::Context) {
+        // The trial or the license can run out while the app is open
+        if self.license.is_none() {
+            self.entitlements = self.trial_entitlements();
+            if !self.can_open(&self.current_tab) {
+                self.current_tab = Tab::Dashboard;
+            }
+        } else {
+            self.refresh_standing();
+        }
+
         // Update clipboard message timer
-        if let Some(_) = self.clipboard_message {
+        if self.clipboard_message.is_some() {
             self.clipboard_timer -= ctx.input(|i| i.unstable_dt);
             if self.clipboard_timer <= 0.0 {
                 self.clipboard_message = None;
             }
         }
 
-        // Update encryption progress
-        if self.encryption_running && self.encryption_progress < 1.0 {
-            self.encryption_progress = (self.encryption_progress + ctx.input(|i| i.unstable_dt * 0.5)).min(1.0);
-            if self.encryption_progress >= 1.0 {
-                self.encryption_running = false;
-                self.add_activity_log("File encrypted".to_string(), "Success".to_string(), "🔒".to_string());
-            }
-            ctx.request_repaint();
-        }
-
         // Update security scan progress
         if self.scan_running && self.scan_progress < 1.0 {
             self.scan_progress = (self.scan_progress + ctx.input(|i| i.unstable_dt * 0.3)).min(1.0);
@@ -438,6 +773,7 @@ impl LicenseApp {
     }
 
     fn add_activity_log(&mut self, action: String, status: String, icon: String) {
+        tracing::info!(target: "license_gate::activity", %status, "{}", action);
         let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
         self.activity_logs.insert(0, ActivityLog {
             timestamp,
@@ -460,19 +796,607 @@ impl LicenseApp {
     }
 
     fn attempt_unlock(&mut self) {
-        self.tries = self.tries.saturating_add(1);
+        // Both the Activate button and the Enter key end up here
+        self.run_guard();
+        let now = Local::now().timestamp();
+        let wait = self.lockout.remaining_secs(now);
+        if wait > 0 {
+            tracing::warn!(wait_secs = wait, "activation attempt during lockout");
+            self.status = format!("⏳ Please wait {} before trying again.", lockout::format_wait(wait));
+            return;
+        }
+
+        if self.pending_activation.is_some() {
+            return;
+        }
+
+        self.normalize_key_input();
+        let key = self.key_input.clone();
+        let format = self.current_key_format();
+        let machine = self.machine.machine_id();
+        let client = self.client.clone();
+        tracing::info!(%format, key = %Secret(&key), "activating product key");
+        self.status.clear();
+        self.pending_activation = Some(Task::spawn(move || {
+            let result = client.activate(&key, &machine);
+            (key, format, result)
+        }));
+    }
+
+    /// Handles the activation server's answer. If the server cannot be
+    /// reached the key is checked offline instead.
+    fn finish_activation(&mut self, key: String, format: KeyFormat, result: Result<ActivateResponse, ClientError>) {
+        let outcome = match result {
+            Ok(response) => response
+                .license
+                .validate(Local::now().date_naive(), &self.machine)
+                .map(|license| (license, Some(response.activation_id)))
+                .map_err(|err| err.to_string()),
+            Err(ClientError::Unreachable(reason)) => {
+                self.add_activity_log(format!("Activation server unreachable, checked key offline ({})", reason),
+                    "Fallback".to_string(), "🖧".to_string());
+                format.verify(&key)
+                    .map(|license| (license, None))
+                    .map_err(|err| err.to_string())
+            }
+            Err(ClientError::Rejected(message) | ClientError::NoSeat(message)) => Err(message),
+        };
+        let outcome = outcome.and_then(|(license, activation_id)| {
+            self.revocations.check(license).map(|license| (license, activation_id)).map_err(|err| err.to_string())
+        });
+
+        match outcome {
+            Ok((license, activation_id)) => {
+                self.lockout.reset();
+                self.lockout.save(self.store.as_ref());
+                // Whichever license came back, the key passed the check
+                // the flag is sealed for.
+                self.activation_key = (format == KeyFormat::Legacy && format.verify(&key).is_ok()).then_some(key);
+                self.activation_id = activation_id;
+                self.activate(license);
+            }
+            Err(message) => {
+                tracing::warn!(key = %Secret(&key), %message, "activation failed");
+                self.lockout.record_failure(Local::now().timestamp());
+                self.lockout.save(self.store.as_ref());
+                self.status = format!("❌ {}. {}", message, text!("Please check your key and try again."));
+            }
+        }
+    }
+
+    fn poll_tasks(&mut self, ctx: &egui::Context) {
+        if let Some(encryption) = &self.encryption {
+            match encryption.poll() {
+                Some(result) => {
+                    let source = encryption.source.clone();
+                    self.encryption = None;
+                    self.finish_encryption(&source, result);
+                }
+                None => ctx.request_repaint_after(Duration::from_millis(100)),
+            }
+        }
 
-        if verify_key(&self.key_input) {
-            self.unlocked = true;
-            self.status = "✅ License activated successfully! All features unlocked.".to_string();
-            self.show_activation = false;
+        if let Some(decryption) = &self.decryption {
+            match decryption.poll() {
+                Some(result) => {
+                    let source = decryption.source.clone();
+                    self.decryption = None;
+                    self.finish_decryption(&source, result);
+                }
+                None => ctx.request_repaint_after(Duration::from_millis(100)),
+            }
+        }
+
+        if let Some(task) = &self.pending_activation {
+            match task.poll() {
+                Some(Ok((key, format, result))) => {
+                    self.pending_activation = None;
+                    self.finish_activation(key, format, result);
+                }
+                Some(Err(err)) => {
+                    self.pending_activation = None;
+                    tracing::warn!(%err, "activation failed");
+                    self.add_activity_log(format!("Activation failed: {}", err), "Failed".to_string(), "⚠".to_string());
+                    self.status = format!("❌ Activation failed: {}", err);
+                }
+                None => ctx.request_repaint_after(Duration::from_millis(100)),
+            }
+        }
+
+        if let Some(task) = &self.unseal {
+            match task.poll() {
+                Some(result) => {
+                    self.unseal = None;
+                    if let Ok((flag, event_secret)) = result {
+                        self.flag = Some(flag);
+                        self.event_secret = event_secret;
+                        if self.tampered.is_some() {
+                            self.hide_flag();
+                        }
+                    }
+                }
+                None => ctx.request_repaint_after(Duration::from_millis(50)),
+            }
+        }
+
+        if let Some(task) = &self.pending_deactivation {
+            match task.poll() {
+                Some(result) => {
+                    self.pending_deactivation = None;
+                    // A worker that died is handled like an unreleased seat.
+                    self.finish_deactivation(result.ok());
+                }
+                None => ctx.request_repaint_after(Duration::from_millis(100)),
+            }
+        }
+
+        if let Some(task) = &self.license_check {
+            if let Some(check) = task.poll() {
+                self.license_check = None;
+                self.next_license_check = Instant::now() + LICENSE_CHECK_INTERVAL;
+                if let Ok(LicenseCheck::Invalid(reason)) = check {
+                    self.drop_license(&reason);
+                }
+            } else {
+                ctx.request_repaint_after(Duration::from_millis(500));
+            }
+        } else if Instant::now() >= self.next_license_check {
+            self.start_license_check();
+        }
+
+        if let Some(task) = &self.revocation_refresh
+            && let Some(result) = task.poll()
+        {
+            self.revocation_refresh = None;
+            // An unreachable server leaves the list on disk in charge.
+            if let Ok(Ok(Some(signed))) = result {
+                self.update_revocations(signed);
+            }
+        }
+
+        if let Some(floating) = &mut self.floating {
+            let outcome = floating.poll();
+            ctx.request_repaint_after(Duration::from_secs(1));
+            match outcome {
+                Some(LeaseOutcome::Granted(file)) => self.accept_lease(file),
+                Some(LeaseOutcome::Renewed(file)) => self.renew_lease(file),
+                Some(LeaseOutcome::Lost(reason)) => self.lose_lease(&reason),
+                None => {}
+            }
+        }
+    }
+
+    /// Loads the signed revocation list kept in the config directory. A list
+    /// that does not verify is ignored.
+    fn load_revocations(&mut self) {
+        let Some(store) = &self.store else { return };
+        let Ok(text) = std::fs::read_to_string(store.path(store::REVOCATION_FILE)) else { return };
+        match SignedRevocationList::parse(&text).and_then(|signed| signed.verify()) {
+            Ok(list) => self.revocations = list,
+            Err(err) => self.add_activity_log(format!("Revocation list ignored: {}", err),
+                "Invalid".to_string(), "⚠".to_string()),
+        }
+    }
+
+    /// Adopts a newer list from the activation server and drops the current
+    /// license if it is on it.
+    fn update_revocations(&mut self, signed: SignedRevocationList) {
+        let list = match signed.verify() {
+            Ok(list) if list.serial > self.revocations.serial => list,
+            Ok(_) => return,
+            Err(err) => {
+                self.add_activity_log(format!("Revocation list from server ignored: {}", err),
+                    "Invalid".to_string(), "⚠".to_string());
+                return;
+            }
+        };
+        if let Some(store) = &self.store {
+            let _ = std::fs::write(store.path(store::REVOCATION_FILE), signed.to_json());
+        }
+        self.revocations = list;
+        if self.license.as_ref().is_some_and(|license| self.revocations.is_revoked(license)) {
+            self.drop_license("License has been revoked");
+        }
+    }
+
+    fn accept_lease(&mut self, file: SignedLicense) {
+        let result = file
+            .validate(Local::now().date_naive(), &self.machine)
+            .and_then(|license| self.revocations.check(license));
+        match result {
+            Ok(license) => {
+                self.add_activity_log("Floating seat acquired".to_string(),
+                    "Leased".to_string(), "🎫".to_string());
+                self.show_activation = false;
+                self.set_license(license);
+            }
+            Err(err) => {
+                let reason = format!("Lease license rejected: {}", err);
+                if let Some(floating) = &mut self.floating {
+                    floating.reject(&reason);
+                }
+                self.add_activity_log(reason, "Rejected".to_string(), "⚠".to_string());
+            }
+        }
+    }
+
+    /// Swaps in the license reissued with a renewed lease. Its fresh expiry
+    /// keeps a seat that is still held out of the grace period and read-only
+    /// mode.
+    fn renew_lease(&mut self, file: SignedLicense) {
+        let result = file
+            .validate(Local::now().date_naive(), &self.machine)
+            .and_then(|license| self.revocations.check(license));
+        match result {
+            Ok(license) => {
+                self.entitlements = license.entitlements();
+                self.license = Some(license);
+                self.standing = Standing::Active;
+                self.refresh_standing();
+            }
+            Err(err) => {
+                let reason = format!("Renewed lease license rejected: {}", err);
+                if let Some(floating) = &mut self.floating {
+                    floating.reject(&reason);
+                }
+                self.lose_lease(&reason);
+            }
+        }
+    }
+
+    /// Returns to trial mode after the floating seat was lost. Nothing is
+    /// stored for leased licenses, so only the in-memory state changes.
+    fn lose_lease(&mut self, reason: &str) {
+        self.license = None;
+        self.flag = None;
+        self.event_secret = None;
+        self.unseal = None;
+        self.entitlements = self.trial_entitlements();
+        self.add_activity_log(reason.to_string(), "Lease lost".to_string(), "⚠".to_string());
+    }
+
+    /// Confirms an online activation with a heartbeat, or asks the server
+    /// whether a signed license file has been revoked.
+    fn start_license_check(&mut self) {
+        self.next_license_check = Instant::now() + LICENSE_CHECK_INTERVAL;
+        let client = self.client.clone();
+        let machine = self.machine.machine_id();
+        if self.floating.as_ref().is_some_and(Floating::has_lease) {
+            return;
+        }
+        let task = match (&self.activation_id, &self.license) {
+            (Some(activation_id), _) => {
+                let activation_id = activation_id.clone();
+                Task::spawn(move || match client.heartbeat(&activation_id, &machine) {
+                    Ok(response) if response.valid => LicenseCheck::Valid,
+                    Ok(response) => LicenseCheck::Invalid(response.message.unwrap_or_else(|| "activation is no longer valid".to_string())),
+                    Err(ClientError::Rejected(message) | ClientError::NoSeat(message)) => LicenseCheck::Invalid(message),
+                    Err(ClientError::Unreachable(_)) => LicenseCheck::Unreachable,
+                })
+            }
+            (None, Some(License::Signed { claims, .. })) => {
+                let license_id = claims.license_id.clone();
+                Task::spawn(move || match client.is_revoked(&license_id) {
+                    Ok(true) => LicenseCheck::Invalid("License has been revoked".to_string()),
+                    Ok(false) | Err(ClientError::Rejected(_) | ClientError::NoSeat(_)) => LicenseCheck::Valid,
+                    Err(ClientError::Unreachable(_)) => LicenseCheck::Unreachable,
+                })
+            }
+            _ => return,
+        };
+        self.license_check = Some(task);
+    }
+
+    /// Returns to trial mode after the server invalidated the license.
+    fn drop_license(&mut self, reason: &str) {
+        self.clear_license();
+        self.add_activity_log(format!("License deactivated: {}", reason),
+            "Revoked".to_string(), "⚠".to_string());
+    }
+
+    /// Removes the license from this machine at the user's request. An
+    /// online activation releases its seat on the server first; otherwise
+    /// the user gets a receipt to hand to the vendor.
+    fn start_deactivation(&mut self) {
+        self.confirm_deactivation = false;
+        if self.pending_deactivation.is_some() || self.license.is_none() {
+            return;
+        }
+        self.add_activity_log("License deactivation requested".to_string(),
+            "Started".to_string(), "🔒".to_string());
+        match self.activation_id.clone() {
+            Some(activation_id) => {
+                let client = self.client.clone();
+                let machine = self.machine.machine_id();
+                self.pending_deactivation = Some(Task::spawn(move || client.deactivate(&activation_id, &machine)));
+            }
+            None => self.finish_deactivation(None),
+        }
+    }
+
+    /// `result` is the server's answer, or `None` when the license was never
+    /// activated online.
+    fn finish_deactivation(&mut self, result: Option<Result<DeactivateResponse, ClientError>>) {
+        let released = match result {
+            Some(Ok(_)) => {
+                self.add_activity_log("Seat released on the activation server".to_string(),
+                    "Released".to_string(), "🖧".to_string());
+                true
+            }
+            Some(Err(ClientError::Unreachable(reason))) => {
+                self.add_activity_log(format!("Activation server unreachable ({})", reason),
+                    "Offline".to_string(), "🖧".to_string());
+                false
+            }
+            Some(Err(ClientError::Rejected(message) | ClientError::NoSeat(message))) => {
+                self.add_activity_log(format!("Activation server did not release the seat: {}", message),
+                    "Not released".to_string(), "⚠".to_string());
+                false
+            }
+            None => false,
+        };
+        if !released && let Some(license) = &self.license {
+            let receipt = license_core::deactivation_code(&self.machine, license, Local::now().date_naive());
+            self.deactivation_receipt = Some(receipt);
+            self.add_activity_log("Offline deactivation receipt created".to_string(),
+                "Receipt".to_string(), "🧾".to_string());
+        }
+        self.clear_license();
+        self.status.clear();
+        self.add_activity_log("License removed from this machine".to_string(),
+            "Deactivated".to_string(), "🔒".to_string());
+    }
+
+    /// Forgets the current license, on disk and in memory, and falls back to
+    /// the trial.
+    fn clear_license(&mut self) {
+        let removed = self.store.as_ref().map(|store| store.remove(store::LICENSE_FILE));
+        if let Some(Err(err)) = removed {
+            self.add_activity_log(format!("Could not remove stored license: {}", err),
+                "Error".to_string(), "⚠".to_string());
+        }
+        self.license = None;
+        self.flag = None;
+        self.event_secret = None;
+        self.unseal = None;
+        self.activation_id = None;
+        self.activation_key = None;
+        self.license_check = None;
+        self.entitlements = self.trial_entitlements();
+        if !self.can_open(&self.current_tab) {
             self.current_tab = Tab::Dashboard;
+        }
+    }
+
+    /// Uppercases the key, strips whitespace, places the dashes and drops
+    /// characters the format never uses. Returns whether the text changed.
+    fn normalize_key_input(&mut self) -> bool {
+        let format = self.current_key_format();
+        let mut rejected = None;
+        let kept: String = self
+            .key_input
+            .chars()
+            .filter(|&c| {
+                let ambiguous = format.is_ambiguous(c);
+                if ambiguous {
+                    rejected = Some(c.to_ascii_uppercase());
+                }
+                !ambiguous
+            })
+            .collect();
+        self.key_warning = rejected.map(|c| {
+            let similar = match c {
+                'O' => '0',
+                '0' => 'O',
+                'I' => '1',
+                _ => 'I',
+            };
+            format!("⚠ '{}' is never used in {} keys (it is too easily mistaken for '{}')", c, format, similar)
+        });
+
+        let normalized = self.key_format.unwrap_or_else(|| KeyFormat::detect(&kept)).normalize(&kept);
+        let changed = normalized != self.key_input;
+        self.key_input = normalized;
+        changed
+    }
+
+    /// The format chosen in the activation window, or the one the key
+    /// typed so far looks like.
+    fn current_key_format(&self) -> KeyFormat {
+        self.key_format.unwrap_or_else(|| KeyFormat::detect(&self.key_input))
+    }
+
+    /// Whether `tab` may be opened with the current entitlements. The
+    /// Encryption tab always can: decrypting files encrypted earlier needs no
+    /// license, so an expired trial never locks anyone out of them.
+    fn can_open(&self, tab: &Tab) -> bool {
+        *tab == Tab::Encryption || tab.feature().is_none_or(|f| self.entitlements.contains(f))
+    }
+
+    fn nav_button(&mut self, ui: &mut egui::Ui, tab: Tab) {
+        let Some(feature) = tab.feature() else { return };
+        let selected = self.current_tab == tab;
+        let enabled = self.can_open(&tab);
+        if ui.add_enabled(enabled, egui::Button::new(features::nav_label(feature)).selected(selected)).clicked() {
+            self.current_tab = tab;
+        }
+    }
+
+    fn load_license_file(&mut self) {
+        let path_opt: Option<PathBuf> = rfd::FileDialog::new()
+            .add_filter("CyberVault License", &["cvlic"])
+            .pick_file();
+        let Some(path) = path_opt else { return };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => {
+                self.status = format!("❌ Could not read {}: {}", path.to_string_lossy(), err);
+                return;
+            }
+        };
+        let result = license_core::verify_license_file(&text, Local::now().date_naive(), &self.machine)
+            .and_then(|license| self.revocations.check(license));
+        match result {
+            Ok(license) => self.activate(license),
+            Err(err) => self.status = format!("❌ {}.", err),
+        }
+    }
+
+    fn apply_offline_response(&mut self) {
+        let result = SignedLicense::from_code(&self.offline_response)
+            .and_then(|signed| signed.validate(Local::now().date_naive(), &self.machine))
+            .and_then(|license| self.revocations.check(license));
+        match result {
+            Ok(license) => {
+                self.offline_response.clear();
+                self.add_activity_log("License activated offline".to_string(),
+                    "Success".to_string(), "🖧".to_string());
+                self.activate(license);
+            }
+            Err(err) => self.status = format!("❌ {}.", err),
+        }
+    }
+
+    fn activate(&mut self, license: License) {
+        let saved = self
+            .store
+            .as_ref()
+            .map(|store| store.save(store::LICENSE_FILE, &StoredLicense::new(&license, self.activation_id.as_deref(), self.activation_key.as_deref())));
+        if let Some(Err(err)) = saved {
+            self.add_activity_log(format!("Could not save license: {}", err),
+                "Error".to_string(), "⚠".to_string());
+        }
+        // A license of its own makes the floating seat unnecessary.
+        if let Some(mut floating) = self.floating.take() {
+            floating.release();
+        }
+        self.status = format!("✅ {} {} {}", text!("License activated successfully!"), license.edition(), text!("features unlocked."));
+        self.set_license(license);
+        self.current_tab = Tab::Dashboard;
+    }
+
+    fn trial_entitlements(&self) -> Entitlements {
+        if self.trial.is_expired(Local::now().date_naive()) {
+            Entitlements::none()
         } else {
-            self.unlocked = false;
-            if self.tries >= 5 {
-                self.status = "❌ Invalid license key. Too many attempts - contact support.".to_string();
-            } else {
-                self.status = format!("❌ Invalid license key. Please check your key and try again.");
+            Edition::Trial.entitlements()
+        }
+    }
+
+    fn set_license(&mut self, license: License) {
+        // Only reached with a license that passed its checks, so a key that
+        // got here by patching them opens the flag to garbage.
+        let key = match &license {
+            License::Key { key, .. } => Some(key.as_str()),
+            License::Signed { .. } => self.activation_key.as_deref(),
+        }
+        .filter(|key| KeyFormat::detect(key) == KeyFormat::Legacy);
+        self.flag = None;
+        self.event_secret = None;
+        self.unseal = key.map(|key| {
+            let key = key.to_string();
+            let event = self.event.clone();
+            Task::spawn(move || {
+                let flag = license_core::unseal_flag(&key, &SEALED_FLAG);
+                (flag, event.map(|event| event.open_secret(&key)))
+            })
+        });
+        self.entitlements = license.entitlements();
+        self.license = Some(license);
+        self.standing = Standing::Active;
+        self.show_activation = false;
+        self.refresh_standing();
+    }
+
+    /// Runs the checks of `anti-debug` builds. A detection is kept for the
+    /// rest of the session and only shows as the decoy flag.
+    fn run_guard(&mut self) {
+        if self.tampered.is_some() {
+            return;
+        }
+        if let Some(reason) = guard::check() {
+            tracing::debug!(%reason, "guard check failed");
+            self.tampered = Some(reason);
+            self.hide_flag();
+        }
+    }
+
+    fn hide_flag(&mut self) {
+        if let Some(flag) = &self.flag {
+            self.flag = Some(guard::decoy_flag(flag));
+        }
+        self.event_secret = None;
+    }
+
+    /// Moves the license into its grace period and then read-only mode once
+    /// it has expired.
+    fn refresh_standing(&mut self) {
+        let Some(license) = &self.license else { return };
+        let today = Local::now().date_naive();
+        let standing = license.standing(today);
+        if standing == self.standing {
+            return;
+        }
+        self.entitlements = license.entitlements_on(today);
+        self.standing = standing;
+        match standing {
+            Standing::Active => {}
+            Standing::Grace { until } => self.add_activity_log(format!("License expired, grace period until {}", until),
+                "Grace".to_string(), "⏳".to_string()),
+            Standing::ReadOnly { .. } => self.add_activity_log("Grace period over, switched to read-only mode".to_string(),
+                "Read-only".to_string(), "⛔".to_string()),
+        }
+    }
+
+    /// Banner shown at the top of tabs whose changes are disabled after the
+    /// grace period.
+    fn read_only_notice(&self, ui: &mut egui::Ui) {
+        if self.entitlements.is_read_only() {
+            ui.label(egui::RichText::new("⛔ Read-only: your license has expired. Renew it to make changes.")
+                .color(egui::Color32::from_rgb(255, 100, 100)));
+            ui.add_space(10.0);
+        }
+    }
+
+    /// Reloads the license accepted in a previous session. A stored license
+    /// that was edited, copied from another machine or no longer verifies is
+    /// deleted.
+    fn restore_license(&mut self) {
+        let Some(store) = &self.store else { return };
+        let stored = match store.load::<StoredLicense>(store::LICENSE_FILE) {
+            Ok(Some(stored)) => stored,
+            Ok(None) => return,
+            Err(StoreError::Io(err)) => {
+                self.add_activity_log(format!("Could not read stored license: {}", err),
+                    "Error".to_string(), "⚠".to_string());
+                return;
+            }
+            Err(StoreError::Tampered) => {
+                let _ = store.remove(store::LICENSE_FILE);
+                self.add_activity_log("Stored license rejected: modified or copied from another machine".to_string(),
+                    "Tampered".to_string(), "⚠".to_string());
+                return;
+            }
+        };
+        let result = match stored {
+            StoredLicense::Key(key) => license_core::verify_key(&key),
+            // An expired license stays in place and degrades instead.
+            StoredLicense::File(file) => file.validate_ignoring_expiry(Local::now().date_naive(), &self.machine),
+            StoredLicense::Activated { file, activation_id, key } => {
+                self.activation_id = Some(activation_id);
+                self.activation_key = key.filter(|key| license_core::verify_legacy_key(key).is_ok());
+                file.validate_ignoring_expiry(Local::now().date_naive(), &self.machine)
+            }
+        };
+        match result.and_then(|license| self.revocations.check(license)) {
+            Ok(license) => self.set_license(license),
+            Err(err) => {
+                let _ = store.remove(store::LICENSE_FILE);
+                self.activation_id = None;
+                self.activation_key = None;
+                let status = if err == LicenseError::Revoked { "Revoked" } else { "Invalid" };
+                self.add_activity_log(format!("Stored license rejected: {}", err),
+                    status.to_string(), "⚠".to_string());
             }
         }
     }
@@ -487,16 +1411,22 @@ impl LicenseApp {
             .corner_radius(8.0)
             .inner_margin(16.0)
             .show(ui, |ui| {
-                if self.unlocked {
+                if let Some(license) = &self.license {
                     ui.label(egui::RichText::new("Welcome to CyberVault Pro!").size(20.0).strong());
                     ui.add_space(8.0);
                     ui.label("Your data is secured with military-grade encryption.");
                     ui.add_space(8.0);
-                    ui.label(egui::RichText::new("🏆 Premium License Active").color(egui::Color32::from_rgb(255, 215, 0)));
+                    ui.label(egui::RichText::new(format!("🏆 {} License Active", license.edition())).color(egui::Color32::from_rgb(255, 215, 0)));
                 } else {
                     ui.label(egui::RichText::new("Trial Version").size(20.0).strong());
                     ui.add_space(8.0);
                     ui.label("You're using the trial version with limited features.");
+                    let days = self.trial.days_remaining(Local::now().date_naive());
+                    if days > 0 {
+                        ui.label(format!("{} of {} trial days remaining.", days, trial::TRIAL_DAYS));
+                    } else {
+                        ui.label(format!("Your trial ended on {}.", self.trial.first_run + chrono::Duration::days(trial::TRIAL_DAYS)));
+                    }
                     ui.add_space(8.0);
                     ui.label(egui::RichText::new("Activate your license to unlock all features!").color(egui::Color32::from_rgb(255, 180, 0)));
                 }
@@ -504,6 +1434,30 @@ impl LicenseApp {
 
         ui.add_space(20.0);
 
+        // Challenge progress
+        let solved = self.challenges.solved_count();
+        let total = self.challenges.pack.levels.len();
+        egui::Frame::new()
+            .fill(egui::Color32::from_rgb(30, 35, 45))
+            .corner_radius(8.0)
+            .inner_margin(16.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("🏁 {}", self.challenges.pack.name)).size(14.0).strong());
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let label = if solved == total { "Review" } else { "Continue" };
+                        if ui.button(label).clicked() {
+                            self.current_tab = Tab::Challenges;
+                        }
+                    });
+                });
+                ui.add_space(6.0);
+                ui.add(egui::ProgressBar::new(solved as f32 / total as f32)
+                    .text(format!("{} of {} levels solved", solved, total)));
+            });
+
+        ui.add_space(20.0);
+
         // Stats section
         ui.label(egui::RichText::new("SECURITY OVERVIEW").size(14.0).color(egui::Color32::GRAY));
         ui.add_space(10.0);
@@ -518,7 +1472,7 @@ impl LicenseApp {
                     ui.set_min_width(150.0);
                     ui.label(egui::RichText::new("🔒 Encrypted Files").size(12.0).color(egui::Color32::GRAY));
                     ui.add_space(4.0);
-                    ui.label(egui::RichText::new(if self.unlocked { "127" } else { "3" }).size(28.0).strong());
+                    ui.label(egui::RichText::new(if self.license.is_some() { "127" } else { "3" }).size(28.0).strong());
                 });
 
             ui.add_space(10.0);
@@ -532,7 +1486,7 @@ impl LicenseApp {
                     ui.set_min_width(150.0);
                     ui.label(egui::RichText::new("🔑 Passwords Secured").size(12.0).color(egui::Color32::GRAY));
                     ui.add_space(4.0);
-                    ui.label(egui::RichText::new(if self.unlocked { "42" } else { "2" }).size(28.0).strong());
+                    ui.label(egui::RichText::new(if self.license.is_some() { "42" } else { "2" }).size(28.0).strong());
                 });
 
             ui.add_space(10.0);
@@ -546,7 +1500,7 @@ impl LicenseApp {
                     ui.set_min_width(150.0);
                     ui.label(egui::RichText::new("⭐ Security Score").size(12.0).color(egui::Color32::GRAY));
                     ui.add_space(4.0);
-                    ui.label(egui::RichText::new(if self.unlocked { "98%" } else { "45%" }).size(28.0).strong());
+                    ui.label(egui::RichText::new(if self.license.is_some() { "98%" } else { "45%" }).size(28.0).strong());
                 });
         });
 
@@ -556,30 +1510,23 @@ impl LicenseApp {
         ui.label(egui::RichText::new("PREMIUM FEATURES").size(14.0).color(egui::Color32::GRAY));
         ui.add_space(10.0);
 
-        let features = vec![
-            ("🔒 Military-Grade Encryption", "AES-256 encryption for all your files", self.unlocked),
-            ("🔑 Password Manager", "Securely store and manage unlimited passwords", self.unlocked),
-            ("🗄 Secure Vault", "Protected storage for sensitive documents", self.unlocked),
-            ("☁️ Cloud Sync", "Automatic backup to encrypted cloud storage", self.unlocked),
-            ("🔔 Security Alerts", "Real-time notifications for security events", self.unlocked),
-            ("📊 Activity Reports", "Detailed logs and analytics", self.unlocked),
-        ];
-
         egui::Grid::new("features_grid")
             .num_columns(2)
             .spacing([20.0, 10.0])
             .show(ui, |ui| {
-                for (i, (title, desc, enabled)) in features.iter().enumerate() {
+                for (i, feature) in Feature::ALL.into_iter().enumerate() {
+                    let (title, desc) = features::card(feature);
+                    let enabled = self.entitlements.contains(feature);
                     egui::Frame::new()
-                        .fill(if *enabled { egui::Color32::from_rgb(30, 35, 45) } else { egui::Color32::from_rgb(35, 30, 30) })
+                        .fill(if enabled { egui::Color32::from_rgb(30, 35, 45) } else { egui::Color32::from_rgb(35, 30, 30) })
                         .corner_radius(6.0)
                         .inner_margin(12.0)
                         .show(ui, |ui| {
                             ui.set_min_width(250.0);
-                            let color = if *enabled { egui::Color32::WHITE } else { egui::Color32::GRAY };
-                            ui.label(egui::RichText::new(*title).size(14.0).color(color).strong());
+                            let color = if enabled { egui::Color32::WHITE } else { egui::Color32::GRAY };
+                            ui.label(egui::RichText::new(title).size(14.0).color(color).strong());
                             ui.add_space(4.0);
-                            ui.label(egui::RichText::new(*desc).size(11.0).color(egui::Color32::GRAY));
+                            ui.label(egui::RichText::new(desc).size(11.0).color(egui::Color32::GRAY));
                             if !enabled {
                                 ui.add_space(4.0);
                                 ui.label(egui::RichText::new("🔒 License Required").size(10.0).color(egui::Color32::from_rgb(255, 180, 0)));
@@ -592,29 +1539,215 @@ impl LicenseApp {
                 }
             });
 
-        if !self.unlocked {
+        if self.license.is_none() {
             ui.add_space(20.0);
             ui.separator();
             ui.add_space(10.0);
             if ui.button(egui::RichText::new("🔓 Activate License Now").size(16.0)).clicked() {
                 self.show_activation = true;
             }
-        } else {
+        } else if let Some(flag) = self.flag.clone() {
             ui.add_space(20.0);
             ui.separator();
             ui.add_space(10.0);
-            ui.label(egui::RichText::new("🎉 Congratulations! You've unlocked all features.").size(14.0).color(egui::Color32::from_rgb(100, 255, 100)));
+            ui.label(egui::RichText::new(text!("🎉 Congratulations! You've unlocked all features.")).size(14.0).color(egui::Color32::from_rgb(100, 255, 100)));
             ui.add_space(6.0);
-            ui.label("Your secret challenge flag:");
-            ui.code(egui::RichText::new("FLAG{cybervault_pro_license_cracked_successfully}").color(egui::Color32::from_rgb(255, 215, 0)));
+            // At an event every player gets their own flag, so one that is
+            // passed around shows up under the wrong name.
+            let flag = match (&self.event, &self.event_secret) {
+                (Some(event), Some(secret)) => {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Player ID ({}):", event.event));
+                        if ui.text_edit_singleline(&mut self.player_id).lost_focus() {
+                            challenges::save_player_id(self.store.as_ref(), &self.player_id);
+                        }
+                    });
+                    if self.player_id.trim().is_empty() {
+                        ui.label(egui::RichText::new(text!("Enter your player ID to get your flag.")).color(egui::Color32::GRAY));
+                        return;
+                    }
+                    license_core::player_flag(secret, &event.event, &self.player_id, self.revealed_hints)
+                }
+                _ => flag,
+            };
+            let score = self.scoring.score(self.revealed_hints);
+            ui.label(text!("Your secret challenge flag:"));
+            ui.horizontal(|ui| {
+                ui.code(egui::RichText::new(flag).color(egui::Color32::from_rgb(255, 215, 0)));
+                ui.label(egui::RichText::new(format!("Score: {} / {}", score, self.scoring.max_score)).strong());
+            });
+            if self.revealed_hints > 0 {
+                ui.small(format!("{} hint(s) used", self.revealed_hints));
+            }
+        }
+    }
+
+    fn show_challenges(&mut self, ui: &mut egui::Ui) {
+        ui.heading("🏁 Challenges");
+        ui.add_space(10.0);
+        ui.label(egui::RichText::new(&self.challenges.pack.name).size(16.0).strong());
+        if !self.challenges.pack.description.is_empty() {
+            ui.label(&self.challenges.pack.description);
+        }
+        ui.add_space(8.0);
+
+        let mut open_pack = false;
+        let mut use_builtin = false;
+        let mut reset = false;
+        ui.horizontal(|ui| {
+            if ui.button("📂 Open Pack").clicked() {
+                open_pack = true;
+            }
+            if !self.challenges.is_builtin() && ui.button("↺ Built-in Pack").clicked() {
+                use_builtin = true;
+            }
+            if ui.button("🗑 Reset Progress").clicked() {
+                reset = true;
+            }
+        });
+        ui.add_space(8.0);
+        let solved = self.challenges.solved_count();
+        let total = self.challenges.pack.levels.len();
+        ui.add(egui::ProgressBar::new(solved as f32 / total as f32)
+            .text(format!("{} of {} levels solved", solved, total)));
+        ui.add_space(15.0);
+
+        let current = self.challenges.current();
+        let mut submit = false;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (index, level) in self.challenges.pack.levels.iter().enumerate() {
+                let solved = self.challenges.is_solved(level);
+                let is_current = current == Some(index);
+                let (icon, color) = if solved {
+                    ("✅", egui::Color32::from_rgb(100, 255, 100))
+                } else if is_current {
+                    ("▶", egui::Color32::WHITE)
+                } else {
+                    ("🔒", egui::Color32::GRAY)
+                };
+                egui::Frame::new()
+                    .fill(egui::Color32::from_rgb(30, 35, 45))
+                    .corner_radius(8.0)
+                    .inner_margin(16.0)
+                    .show(ui, |ui| {
+                        ui.set_min_width(ui.available_width());
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(format!("{} Level {}: {}", icon, index + 1, level.title)).color(color).strong());
+                            ui.label(egui::RichText::new(format!("({})", level.algorithm.label())).size(11.0).color(egui::Color32::GRAY));
+                        });
+                        if solved {
+                            if let Some(reward) = self.challenges.reward(level) {
+                                ui.add_space(4.0);
+                                ui.code(egui::RichText::new(reward).color(egui::Color32::from_rgb(255, 215, 0)));
+                            }
+                        } else if is_current {
+                            if !level.description.is_empty() {
+                                ui.add_space(4.0);
+                                ui.label(&level.description);
+                            }
+                            ui.add_space(8.0);
+                            ui.horizontal(|ui| {
+                                ui.label("Answer:");
+                                let response = ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut self.challenge_answer));
+                                let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                if ui.add_enabled(!self.challenge_answer.trim().is_empty(), egui::Button::new("Submit")).clicked() || entered {
+                                    submit = true;
+                                }
+                            });
+                        }
+                    });
+                ui.add_space(6.0);
+            }
+            if current.is_none() {
+                ui.add_space(6.0);
+                ui.label(egui::RichText::new("🎉 Pack complete!").size(14.0).color(egui::Color32::from_rgb(100, 255, 100)));
+            }
+            if !self.challenge_status.is_empty() {
+                ui.add_space(6.0);
+                ui.label(&self.challenge_status);
+            }
+        });
+
+        if submit {
+            self.submit_challenge();
+        }
+        if open_pack {
+            self.open_challenge_pack();
+        }
+        if use_builtin {
+            self.challenges.use_builtin(self.store.as_ref());
+            self.challenge_status.clear();
+        }
+        if reset {
+            self.challenges.reset(self.store.as_ref());
+            self.challenge_status.clear();
+            self.add_activity_log(format!("Challenge progress reset: {}", self.challenges.pack.name),
+                "Reset".to_string(), "🏁".to_string());
+        }
+    }
+
+    fn submit_challenge(&mut self) {
+        let answer = self.challenge_answer.trim().to_string();
+        if answer.is_empty() {
+            return;
+        }
+        match self.challenges.submit(&answer, self.store.as_ref()) {
+            Some(level) => {
+                let title = level.title.clone();
+                self.challenge_answer.clear();
+                self.challenge_status = format!("✅ Solved: {}", title);
+                self.add_activity_log(format!("Challenge solved: {}", title),
+                    "Solved".to_string(), "🏁".to_string());
+            }
+            None => {
+                tracing::debug!(answer = %Secret(&answer), "challenge answer rejected");
+                self.challenge_status = "❌ Not accepted. Try again.".to_string();
+            }
+        }
+    }
+
+    /// Progress is saved under the event's name, or `""` for the built-in
+    /// hints.
+    fn event_name(&self) -> &str {
+        self.event.as_ref().map_or("", |event| event.event.as_str())
+    }
+
+    fn reveal_hint(&mut self) {
+        let Some(hint) = self.scoring.hints.get(self.revealed_hints) else { return };
+        let penalty = hint.penalty;
+        self.revealed_hints += 1;
+        challenges::save_revealed_hints(self.store.as_ref(), self.event_name(), self.revealed_hints);
+        self.add_activity_log(format!("Hint {} of {} revealed", self.revealed_hints, self.scoring.hints.len()),
+            format!("−{} points", penalty), "💡".to_string());
+    }
+
+    fn open_challenge_pack(&mut self) {
+        let path_opt: Option<PathBuf> = rfd::FileDialog::new()
+            .add_filter("Challenge Pack", &["cvpack", "json"])
+            .pick_file();
+        let Some(path) = path_opt else { return };
+        match self.challenges.open_pack(&path, self.store.as_ref()) {
+            Ok(()) => {
+                self.challenge_answer.clear();
+                self.challenge_status.clear();
+                self.add_activity_log(format!("Challenge pack opened: {}", self.challenges.pack.name),
+                    "Loaded".to_string(), "🏁".to_string());
+            }
+            Err(err) => self.challenge_status = format!("❌ {}", err),
         }
     }
 
     fn show_encryption(&mut self, ui: &mut egui::Ui) {
         ui.heading("🔒 File Encryption");
         ui.add_space(10.0);
-        ui.label("Encrypt your sensitive files with AES-256 encryption.");
+        ui.label("Encrypt your sensitive files with AES-256 encryption, and decrypt them again.");
         ui.add_space(15.0);
+        self.read_only_notice(ui);
+        if !self.entitlements.contains(Feature::Encryption) {
+            ui.label(egui::RichText::new("🔒 Encrypting needs a license. Decrypting your existing files stays available.")
+                .color(egui::Color32::from_rgb(255, 180, 0)));
+            ui.add_space(10.0);
+        }
 
         egui::Frame::new()
             .fill(egui::Color32::from_rgb(30, 35, 45))
@@ -636,22 +1769,100 @@ impl LicenseApp {
                     }
                 });
 
+                ui.add_space(8.0);
+                egui::Grid::new("encryption_passphrase").num_columns(2).spacing([8.0, 6.0]).show(ui, |ui| {
+                    ui.label("Passphrase:");
+                    ui.add(egui::TextEdit::singleline(&mut self.encryption_passphrase).password(true));
+                    ui.end_row();
+                    ui.label("Confirm:");
+                    ui.add(egui::TextEdit::singleline(&mut self.encryption_confirm).password(true));
+                    ui.end_row();
+                });
+                let mismatch = self.encryption_passphrase != self.encryption_confirm;
+                if mismatch && !self.encryption_confirm.is_empty() {
+                    ui.small(egui::RichText::new("Passphrases do not match").color(egui::Color32::from_rgb(255, 180, 0)));
+                }
+
                 ui.add_space(12.0);
 
-                if ui.add_enabled(!self.encryption_running && !self.file_to_encrypt.is_empty(), 
+                let can_encrypt = self.entitlements.can_modify(Feature::Encryption);
+                let ready = !self.file_to_encrypt.is_empty() && !self.encryption_passphrase.is_empty() && !mismatch;
+                if ui.add_enabled(can_encrypt && self.encryption.is_none() && ready,
                     egui::Button::new(egui::RichText::new("🔒 Encrypt File").size(14.0))).clicked() {
-                    self.encryption_progress = 0.0;
-                    self.encryption_running = true;
+                    self.start_encryption();
                 }
 
-                if self.encryption_progress > 0.0 {
+                if let Some(encryption) = &self.encryption {
                     ui.add_space(10.0);
-                    ui.add(egui::ProgressBar::new(self.encryption_progress).text("Encrypting..."));
-                    if self.encryption_progress >= 1.0 && !self.encryption_running {
+                    ui.add(egui::ProgressBar::new(encryption.progress()).show_percentage().text("Encrypting..."));
+                }
+                match &self.encryption_status {
+                    Some(Ok(message)) => {
                         ui.add_space(6.0);
                         ui.label(egui::RichText::new("✓ File encrypted successfully!").color(egui::Color32::from_rgb(100, 255, 100)));
-                        ui.label(format!("Encrypted: {}", self.file_to_encrypt));
+                        ui.label(message);
                     }
+                    Some(Err(message)) => {
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new(format!("❌ {}", message)).color(egui::Color32::from_rgb(255, 100, 100)));
+                    }
+                    None => {}
+                }
+            });
+
+        ui.add_space(15.0);
+
+        egui::Frame::new()
+            .fill(egui::Color32::from_rgb(30, 35, 45))
+            .corner_radius(8.0)
+            .inner_margin(16.0)
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("Decrypt File").strong());
+                ui.add_space(8.0);
+
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.file_to_decrypt);
+                    if ui.button("📁 Browse").clicked() {
+                        let path_opt: Option<PathBuf> = rfd::FileDialog::new()
+                            .add_filter("Encrypted Files", &[encryption::EXTENSION])
+                            .add_filter("All Files", &["*"])
+                            .pick_file();
+                        if let Some(path) = path_opt {
+                            self.file_to_decrypt = path.to_string_lossy().into_owned();
+                        }
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label("Passphrase:");
+                    ui.add(egui::TextEdit::singleline(&mut self.decryption_passphrase).password(true));
+                });
+
+                ui.add_space(12.0);
+
+                // Not gated on Feature::Encryption; see `can_open`.
+                let ready = !self.file_to_decrypt.is_empty() && !self.decryption_passphrase.is_empty();
+                if ui.add_enabled(self.decryption.is_none() && ready,
+                    egui::Button::new(egui::RichText::new("🔓 Decrypt File").size(14.0))).clicked() {
+                    self.start_decryption();
+                }
+
+                if let Some(decryption) = &self.decryption {
+                    ui.add_space(10.0);
+                    ui.add(egui::ProgressBar::new(decryption.progress()).show_percentage().text("Decrypting..."));
+                }
+                match &self.decryption_status {
+                    Some(Ok(message)) => {
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new("✓ File decrypted successfully!").color(egui::Color32::from_rgb(100, 255, 100)));
+                        ui.label(message);
+                    }
+                    Some(Err(message)) => {
+                        ui.add_space(6.0);
+                        ui.label(egui::RichText::new(format!("❌ {}", message)).color(egui::Color32::from_rgb(255, 100, 100)));
+                    }
+                    None => {}
                 }
             });
 
@@ -660,16 +1871,89 @@ impl LicenseApp {
         ui.label(egui::RichText::new("ENCRYPTION SETTINGS").size(14.0).color(egui::Color32::GRAY));
         ui.add_space(10.0);
 
-        ui.checkbox(&mut self.use_aes256, "Use AES-256 encryption");
+        ui.label("AES-256-GCM, with the key derived from the passphrase by Argon2id.");
         ui.checkbox(&mut self.delete_original, "Delete original after encryption");
         ui.checkbox(&mut self.add_timestamp, "Add timestamp to encrypted files");
     }
 
+    fn start_encryption(&mut self) {
+        let source = PathBuf::from(self.file_to_encrypt.trim());
+        let options = encryption::Options { delete_original: self.delete_original, add_timestamp: self.add_timestamp };
+        match Job::encrypt(source, self.encryption_passphrase.clone(), options) {
+            Ok(encryption) => {
+                tracing::info!(file = %encryption.source.display(), "encrypting file");
+                self.encryption = Some(encryption);
+                self.encryption_status = None;
+            }
+            Err(err) => self.encryption_failed(err),
+        }
+    }
+
+    fn finish_encryption(&mut self, source: &std::path::Path, result: Result<encryption::Finished, String>) {
+        let encrypted = match result {
+            Ok(encrypted) => encrypted,
+            Err(err) => {
+                self.encryption_failed(err);
+                return;
+            }
+        };
+        self.encryption_passphrase.clear();
+        self.encryption_confirm.clear();
+        let mut message = format!("Encrypted: {}", encrypted.output.display());
+        self.add_activity_log(format!("File encrypted: {}", source.display()), "Success".to_string(), "🔒".to_string());
+        if let Some(err) = encrypted.delete_error {
+            self.add_activity_log(err.clone(), "Warning".to_string(), "⚠".to_string());
+            message = format!("{}\n⚠ {}", message, err);
+        } else if encrypted.options.delete_original {
+            message = format!("{}\nOriginal deleted.", message);
+        }
+        self.encryption_status = Some(Ok(message));
+    }
+
+    fn encryption_failed(&mut self, err: String) {
+        tracing::warn!(%err, "file encryption failed");
+        self.add_activity_log(err.clone(), "Failed".to_string(), "⚠".to_string());
+        self.encryption_status = Some(Err(err));
+    }
+
+    fn start_decryption(&mut self) {
+        let source = PathBuf::from(self.file_to_decrypt.trim());
+        match Job::decrypt(source, self.decryption_passphrase.clone()) {
+            Ok(decryption) => {
+                tracing::info!(file = %decryption.source.display(), "decrypting file");
+                self.decryption = Some(decryption);
+                self.decryption_status = None;
+            }
+            Err(err) => self.decryption_failed(err),
+        }
+    }
+
+    fn finish_decryption(&mut self, source: &std::path::Path, result: Result<encryption::Finished, String>) {
+        let decrypted = match result {
+            Ok(decrypted) => decrypted,
+            Err(err) => {
+                self.decryption_failed(err);
+                return;
+            }
+        };
+        self.decryption_passphrase.clear();
+        self.add_activity_log(format!("File decrypted: {}", source.display()), "Success".to_string(), "🔓".to_string());
+        self.decryption_status = Some(Ok(format!("Decrypted: {}", decrypted.output.display())));
+    }
+
+    fn decryption_failed(&mut self, err: String) {
+        tracing::warn!(%err, "file decryption failed");
+        self.add_activity_log(err.clone(), "Failed".to_string(), "⚠".to_string());
+        self.decryption_status = Some(Err(err));
+    }
+
     fn show_passwords(&mut self, ui: &mut egui::Ui) {
         ui.heading("🔑 Password Manager");
         ui.add_space(10.0);
         ui.label("Store and manage your passwords securely.");
         ui.add_space(15.0);
+        self.read_only_notice(ui);
+        let can_modify = self.entitlements.can_modify(Feature::Passwords);
 
         // Add new password section
         egui::Frame::new()
@@ -692,14 +1976,16 @@ impl LicenseApp {
 
                 ui.add_space(8.0);
 
-                if ui.button("➕ Add Password").clicked() {
-                    if !self.new_site.is_empty() && !self.new_password.is_empty() {
-                        self.passwords.insert(self.new_site.clone(), self.new_password.clone());
-                        self.add_activity_log(format!("Password added for {}", self.new_site.clone()), 
-                            "Success".to_string(), "🔑".to_string());
-                        self.new_site.clear();
-                        self.new_password.clear();
-                    }
+                if ui.add_enabled(can_modify, egui::Button::new("➕ Add Password")).clicked()
+                    && !self.new_site.is_empty()
+                    && !self.new_password.is_empty()
+                {
+                    tracing::debug!(site = %self.new_site, password = %Secret(&self.new_password), "password stored");
+                    self.passwords.insert(self.new_site.clone(), self.new_password.clone());
+                    self.add_activity_log(format!("Password added for {}", self.new_site.clone()), 
+                        "Success".to_string(), "🔑".to_string());
+                    self.new_site.clear();
+                    self.new_password.clear();
                 }
             });
 
@@ -724,7 +2010,7 @@ impl LicenseApp {
                         ui.horizontal(|ui| {
                             ui.label(egui::RichText::new(site).strong());
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                if ui.small_button("🗑").clicked() {
+                                if ui.add_enabled(can_modify, egui::Button::new("🗑").small()).clicked() {
                                     sites_to_remove.push(site.clone());
                                 }
                                 if ui.small_button("📋").clicked() {
@@ -772,9 +2058,11 @@ impl LicenseApp {
         ui.add_space(10.0);
         ui.label("Your encrypted files in the secure vault.");
         ui.add_space(15.0);
+        self.read_only_notice(ui);
+        let can_modify = self.entitlements.can_modify(Feature::Vault);
 
         ui.horizontal(|ui| {
-            if ui.button("➕ Add File").clicked() {
+            if ui.add_enabled(can_modify, egui::Button::new("➕ Add File")).clicked() {
                 let path_opt: Option<PathBuf> = rfd::FileDialog::new()
                     .add_filter("All Files", &["*"])
                     .pick_file();
@@ -813,7 +2101,7 @@ impl LicenseApp {
                         "Success".to_string(), "🗄".to_string());
                 }
             }
-            if ui.button("📥 Import").clicked() {
+            if ui.add_enabled(can_modify, egui::Button::new("📥 Import")).clicked() {
                 let path_opt: Option<PathBuf> = rfd::FileDialog::new()
                     .add_filter("All Files", &["*"])
                     .pick_file();
@@ -877,7 +2165,7 @@ impl LicenseApp {
                                     download_actions.push(item_name);
                                 }
                             }
-                            if ui.small_button("🗑").clicked() {
+                            if ui.add_enabled(can_modify, egui::Button::new("🗑").small()).clicked() {
                                 items_to_remove.push(idx);
                             }
                         });
@@ -908,6 +2196,8 @@ impl LicenseApp {
         ui.add_space(10.0);
         ui.label("View all security events and user activities.");
         ui.add_space(15.0);
+        self.read_only_notice(ui);
+        let can_modify = self.entitlements.can_modify(Feature::ActivityLog);
 
         ui.horizontal(|ui| {
             if ui.button("🔄 Refresh").clicked() {
@@ -925,7 +2215,7 @@ impl LicenseApp {
                         "Success".to_string(), "📥".to_string());
                 }
             }
-            if ui.button("🗑 Clear").clicked() {
+            if ui.add_enabled(can_modify, egui::Button::new("🗑 Clear")).clicked() {
                 self.activity_logs.clear();
                 self.add_activity_log("Activity log cleared".to_string(), 
                     "Success".to_string(), "🗑".to_string());
@@ -1032,6 +2322,8 @@ impl LicenseApp {
         ui.add_space(10.0);
         ui.label("Create backups and restore your encrypted data.");
         ui.add_space(15.0);
+        self.read_only_notice(ui);
+        let can_modify = self.entitlements.can_modify(Feature::BackupRestore);
 
         egui::Frame::new()
             .fill(egui::Color32::from_rgb(30, 35, 45))
@@ -1060,7 +2352,7 @@ impl LicenseApp {
                 ui.add_space(12.0);
 
                 ui.horizontal(|ui| {
-                    if ui.button(egui::RichText::new("💾 Create Backup").size(14.0)).clicked() {
+                    if ui.add_enabled(can_modify, egui::Button::new(egui::RichText::new("💾 Create Backup").size(14.0))).clicked() {
                         let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
                         self.last_backup = timestamp.clone();
                         let backup_type = if self.backup_history.is_empty() || 
@@ -1078,7 +2370,7 @@ impl LicenseApp {
                         self.add_activity_log(format!("Backup created: {}", backup_type), 
                             "Success".to_string(), "💾".to_string());
                     }
-                    if ui.button(egui::RichText::new("📥 Restore Backup").size(14.0)).clicked() {
+                    if ui.add_enabled(can_modify, egui::Button::new(egui::RichText::new("📥 Restore Backup").size(14.0))).clicked() {
                         let path_opt: Option<PathBuf> = rfd::FileDialog::new()
                             .add_filter("Backup Files", &["bak", "backup"])
                             .pick_file();
@@ -1108,10 +2400,10 @@ impl LicenseApp {
                         ui.label(backup_type);
                         ui.label(size);
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.small_button("📥 Restore").clicked() {
+                            if ui.add_enabled(can_modify, egui::Button::new("📥 Restore").small()).clicked() {
                                 restore_actions.push((date.clone(), backup_type.clone()));
                             }
-                            if ui.small_button("🗑").clicked() {
+                            if ui.add_enabled(can_modify, egui::Button::new("🗑").small()).clicked() {
                                 backups_to_remove.push(idx);
                             }
                         });
@@ -1227,26 +2519,131 @@ impl LicenseApp {
 
         ui.add_space(20.0);
 
+        if let Some(log) = &mut self.logging {
+            ui.label(egui::RichText::new("DIAGNOSTICS").size(14.0).color(egui::Color32::GRAY));
+            ui.add_space(10.0);
+
+            egui::Frame::new()
+                .fill(egui::Color32::from_rgb(30, 35, 45))
+                .corner_radius(8.0)
+                .inner_margin(16.0)
+                .show(ui, |ui| {
+                    let mut selected = log.filter.clone();
+                    ui.horizontal(|ui| {
+                        ui.label("Log level:");
+                        egui::ComboBox::from_id_salt("log_level")
+                            .selected_text(&selected)
+                            .show_ui(ui, |ui| {
+                                for level in logging::LEVELS {
+                                    ui.selectable_value(&mut selected, level.to_string(), level);
+                                }
+                            });
+                    });
+                    if selected != log.filter {
+                        let _ = log.set_filter(&selected);
+                    }
+                    match &log.dir {
+                        Some(dir) => ui.small(format!("Log files: {}", dir.display())),
+                        None => ui.small("Log files: unavailable, logging to the console only"),
+                    };
+                    ui.small("Product keys and passwords are redacted in the log.");
+                });
+
+            ui.add_space(20.0);
+        }
+
         ui.label(egui::RichText::new("LICENSE INFORMATION").size(14.0).color(egui::Color32::GRAY));
         ui.add_space(10.0);
 
+        let mut deactivate = false;
+        let mut copy_receipt = None;
+        let mut dismiss_receipt = false;
+
         egui::Frame::new()
             .fill(egui::Color32::from_rgb(30, 35, 45))
             .corner_radius(8.0)
             .inner_margin(16.0)
             .show(ui, |ui| {
-                ui.label(format!("License Type: {}", if self.unlocked { "Enterprise Pro" } else { "Trial" }));
-                ui.label(format!("Version: 3.2.1"));
-                ui.label(format!("Build: 20260201"));
+                match &self.license {
+                    Some(License::Signed { claims, .. }) => {
+                        ui.label(format!("License Type: {}", claims.edition));
+                        ui.label(format!("Licensed To: {}", claims.licensee));
+                        ui.label(format!("License ID: {}", claims.license_id));
+                        ui.label(format!("Issued: {}", claims.issued));
+                        match claims.expires {
+                            Some(expires) => ui.label(format!("Expires: {} (then {} days of grace)", expires,
+                                claims.grace_days.unwrap_or(license_core::DEFAULT_GRACE_DAYS))),
+                            None => ui.label("Expires: Never"),
+                        };
+                        ui.label(format!("Seats: {}", claims.seats));
+                        if claims.machine.is_some() {
+                            ui.label("Bound To: this machine (offline activation)");
+                        }
+                        if !claims.features.is_empty() {
+                            ui.label(format!("Features: {}", claims.features.join(", ")));
+                        }
+                    }
+                    Some(license @ License::Key { .. }) => {
+                        ui.label(format!("License Type: {} (product key)", license.edition()));
+                    }
+                    None => {
+                        ui.label("License Type: Trial");
+                        ui.label(format!("Trial Started: {}", self.trial.first_run));
+                        ui.label(format!("Days Remaining: {}", self.trial.days_remaining(Local::now().date_naive())));
+                    }
+                }
+                ui.label(format!("Version: {}", APP_VERSION));
+                ui.label("Build: 20260201");
                 ui.add_space(8.0);
-                if !self.unlocked {
+                if self.license.is_none() {
                     if ui.button("🔓 Activate License").clicked() {
                         self.show_activation = true;
                     }
+                } else if self.floating.as_ref().is_some_and(Floating::has_lease) {
+                    ui.label(egui::RichText::new(text!("✓ Licensed (floating seat, released on exit)")).color(egui::Color32::from_rgb(100, 255, 100)));
                 } else {
-                    ui.label(egui::RichText::new("✓ Licensed").color(egui::Color32::from_rgb(100, 255, 100)));
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(text!("✓ Licensed")).color(egui::Color32::from_rgb(100, 255, 100)));
+                        if self.pending_deactivation.is_some() {
+                            ui.spinner();
+                            ui.label("Deactivating...");
+                        } else if self.confirm_deactivation {
+                            ui.label("Remove the license from this machine?");
+                            if ui.button("Deactivate").clicked() {
+                                deactivate = true;
+                            }
+                            if ui.button("Cancel").clicked() {
+                                self.confirm_deactivation = false;
+                            }
+                        } else if ui.button("🔒 Deactivate License").clicked() {
+                            self.confirm_deactivation = true;
+                        }
+                    });
+                }
+
+                if let Some(receipt) = &self.deactivation_receipt {
+                    ui.add_space(8.0);
+                    ui.small("The activation server was not told about this deactivation. Send this receipt to the CyberVault portal to use the license on another machine:");
+                    ui.horizontal(|ui| {
+                        ui.code(receipt);
+                        if ui.small_button("📋").clicked() {
+                            copy_receipt = Some(receipt.clone());
+                        }
+                        if ui.small_button("✖").clicked() {
+                            dismiss_receipt = true;
+                        }
+                    });
                 }
             });
+        if deactivate {
+            self.start_deactivation();
+        }
+        if let Some(receipt) = copy_receipt {
+            self.copy_to_clipboard(&receipt);
+        }
+        if dismiss_receipt {
+            self.deactivation_receipt = None;
+        }
 
         ui.add_space(20.0);
 
@@ -1260,38 +2657,10 @@ impl LicenseApp {
             .show(ui, |ui| {
                 ui.label("CyberVault Pro - Secure Data Manager");
                 ui.label("Version 3.2.1 (2026)");
+                ui.label(format!("Challenge Tier: {}", Tier::ACTIVE));
                 ui.add_space(8.0);
-                ui.small("This is a reverse engineering challenge application.");
-                ui.small("Find the correct license key to unlock all features.");
+                ui.small(text!("This is a reverse engineering challenge application."));
+                ui.small(text!("Find the correct license key to unlock all features."));
             });
     }
 }
-
-fn verify_key(input: &str) -> bool {
-    let b = input.as_bytes();
-    if b.len() != 14 { return false; }
-    if b[4] != b'-' || b[9] != b'-' { return false; }
-
-    if input.starts_with("TEST") || input.contains("AAAA") { return false; }
-
-    let mut x: u32 = 0x1234_5678;
-
-    let mut i: usize = 0;
-    let mut state: u8 = 0;
-    while i < b.len() {
-        match state {
-            0 => { x = mix(x, b[i]); state = 1; }
-            1 => { x ^= 0x1111_1111; state = 2; }
-            _ => { i += 1; state = 0; }
-        }
-    }
-    eprintln!("DEBUG x = 0x{:08X}", x);
-    //x == 0xDEAD_BEEF
-    x == 0x85FD063D
-}
-
-fn mix(mut x: u32, byte: u8) -> u32 {
-    x ^= byte as u32;
-    x = x.rotate_left(5).wrapping_add(0x9E37_79B9);
-    x ^ 0xA5A5_5A5A
-}
\ No newline at end of file
@@ -0,0 +1,170 @@
+//! Tamper-evident application state in the platform config directory
+//! (`$XDG_CONFIG_HOME/cybervault-pro` on Linux) and data directory
+//! (`$XDG_DATA_HOME/cybervault-pro`).
+//!
+//! Every file is a JSON document `{ "data": ..., "mac": "..." }` where `mac`
+//! is an HMAC-SHA256 of `data` keyed by the machine id (`/etc/machine-id`).
+//! Editing a file by hand, or copying it to another machine, breaks the MAC.
+//! The hostname and network adapters are left out of the key, so renaming
+//! the machine or swapping a network card does not make every file look
+//! tampered with.
+//!
+//! Documents that carry a vendor signature of their own, such as the
+//! revocation list, are kept as-is next to the sealed files.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use hmac::{Hmac, Mac};
+use license_core::{License, MachineFingerprint, SignedLicense};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const LICENSE_FILE: &str = "license.json";
+/// Signed revocation list (`SignedRevocationList`), stored unsealed.
+pub const REVOCATION_FILE: &str = "revocations.cvrl";
+
+pub enum StoreError {
+    Io(io::Error),
+    /// The file exists but its MAC does not match, or it does not parse.
+    Tampered,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    data: Value,
+    mac: String,
+}
+
+pub struct Store {
+    dir: PathBuf,
+    key: [u8; 32],
+    /// Key of earlier versions, which covered the whole fingerprint. Files
+    /// sealed with it still load and are resealed with `key` on the next save.
+    legacy_key: [u8; 32],
+}
+
+impl Store {
+    /// Opens the store for the current user, or `None` if the platform has no
+    /// config directory.
+    pub fn open() -> Option<Self> {
+        let dirs = ProjectDirs::from("com", "CyberVault", "CyberVault Pro")?;
+        Some(Self::in_dir(dirs.config_dir().to_path_buf()))
+    }
+
+    /// A second store in the local data directory, used for markers that must
+    /// survive the config directory being deleted.
+    pub fn open_data() -> Option<Self> {
+        let dirs = ProjectDirs::from("com", "CyberVault", "CyberVault Pro")?;
+        Some(Self::in_dir(dirs.data_local_dir().to_path_buf()))
+    }
+
+    pub(crate) fn in_dir(dir: PathBuf) -> Self {
+        let machine = MachineFingerprint::collect();
+        let mut hasher = Sha256::new();
+        hasher.update(b"cybervault-store-v2");
+        hasher.update(machine.machine_id.as_deref().unwrap_or("").as_bytes());
+        let key = hasher.finalize().into();
+        let mut hasher = Sha256::new();
+        hasher.update(b"cybervault-store-v1");
+        hasher.update(machine.digest());
+        Self { dir, key, legacy_key: hasher.finalize().into() }
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, StoreError> {
+        let text = match fs::read_to_string(self.dir.join(name)) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(StoreError::Io(err)),
+        };
+        let sealed: Sealed = serde_json::from_str(&text).map_err(|_| StoreError::Tampered)?;
+        let mac = hex::decode(&sealed.mac).map_err(|_| StoreError::Tampered)?;
+        let verifies = |key| Self::mac(key, &sealed.data).verify_slice(&mac).is_ok();
+        if !verifies(&self.key) && !verifies(&self.legacy_key) {
+            return Err(StoreError::Tampered);
+        }
+        serde_json::from_value(sealed.data)
+            .map(Some)
+            .map_err(|_| StoreError::Tampered)
+    }
+
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> io::Result<()> {
+        let data = serde_json::to_value(value).map_err(io::Error::other)?;
+        let mac = hex::encode(Self::mac(&self.key, &data).finalize().into_bytes());
+        let text = serde_json::to_string_pretty(&Sealed { data, mac }).map_err(io::Error::other)?;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(name), text)
+    }
+
+    /// Location of an unsealed, vendor-signed document.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    pub fn remove(&self, name: &str) -> io::Result<()> {
+        match fs::remove_file(self.dir.join(name)) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    }
+
+    /// MAC over the compact serialization of `data`. `Value` objects keep their
+    /// keys sorted, so this is stable across save and load.
+    fn mac(key: &[u8; 32], data: &Value) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(data.to_string().as_bytes());
+        mac
+    }
+}
+
+/// The on-disk form of an accepted license. It is re-verified on every load.
+#[derive(Serialize, Deserialize)]
+pub enum StoredLicense {
+    Key(String),
+    File(SignedLicense),
+    /// A license issued by the activation server, with the handle used for
+    /// heartbeats and the legacy key it was activated with, if any.
+    Activated {
+        file: SignedLicense,
+        activation_id: String,
+        #[serde(default)]
+        key: Option<String>,
+    },
+}
+
+impl StoredLicense {
+    pub fn new(license: &License, activation_id: Option<&str>, key: Option<&str>) -> Self {
+        match (license, activation_id) {
+            (License::Key { key, .. }, _) => StoredLicense::Key(key.clone()),
+            (License::Signed { file, .. }, None) => StoredLicense::File(file.clone()),
+            (License::Signed { file, .. }, Some(id)) => StoredLicense::Activated {
+                file: file.clone(),
+                activation_id: id.to_string(),
+                key: key.map(str::to_string),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_files_sealed_with_the_legacy_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::in_dir(dir.path().to_path_buf());
+        let legacy = Store { key: store.legacy_key, ..Store::in_dir(dir.path().to_path_buf()) };
+        legacy.save("old.json", &42u32).unwrap();
+        assert!(matches!(store.load::<u32>("old.json"), Ok(Some(42))));
+
+        let other = Store { key: [1; 32], legacy_key: [2; 32], dir: dir.path().to_path_buf() };
+        assert!(matches!(other.load::<u32>("old.json"), Err(StoreError::Tampered)));
+    }
+}
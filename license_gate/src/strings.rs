@@ -0,0 +1,52 @@
+//! UI text that points at the license check. Hard builds keep it encrypted in
+//! the binary, so searching for "activated successfully" does not lead
+//! straight to the code that follows a valid key; other tiers use the
+//! literals as they are.
+//!
+//! `text!("...")` yields a `String` in every tier.
+
+/// Encrypts `bytes` at compile time.
+#[cfg(feature = "hard")]
+pub const fn seal<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = bytes[i] ^ keystream(i);
+        i += 1;
+    }
+    out
+}
+
+#[cfg(feature = "hard")]
+pub fn open(sealed: &[u8]) -> String {
+    let bytes: Vec<u8> = sealed.iter().enumerate().map(|(i, &b)| b ^ keystream(i)).collect();
+    String::from_utf8(bytes).expect("sealed UI text is UTF-8")
+}
+
+/// A xorshift over the byte position. Only meant to keep the text out of
+/// `strings`, not to withstand analysis.
+#[cfg(feature = "hard")]
+const fn keystream(i: usize) -> u8 {
+    let mut x = (i as u32).wrapping_mul(0x9E37_79B9) ^ 0xC0DE_F00D;
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    (x >> 24) as u8
+}
+
+#[cfg(feature = "hard")]
+macro_rules! text {
+    ($text:literal) => {{
+        const SEALED: [u8; $text.len()] = $crate::strings::seal($text.as_bytes());
+        $crate::strings::open(&SEALED)
+    }};
+}
+
+#[cfg(not(feature = "hard"))]
+macro_rules! text {
+    ($text:literal) => {
+        String::from($text)
+    };
+}
+
+pub(crate) use text;
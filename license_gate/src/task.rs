@@ -0,0 +1,39 @@
+//! Work that runs off the UI thread and is polled once per frame.
+
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+pub struct Task<T> {
+    receiver: Receiver<T>,
+}
+
+/// The worker panicked before producing a result.
+#[derive(Debug)]
+pub struct Panicked;
+
+impl fmt::Display for Panicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("background task stopped unexpectedly")
+    }
+}
+
+impl<T: Send + 'static> Task<T> {
+    pub fn spawn(work: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(work());
+        });
+        Self { receiver }
+    }
+
+    /// Returns the result once the work has finished, or [`Panicked`] if the
+    /// worker died without one. `None` while it is still running.
+    pub fn poll(&self) -> Option<Result<T, Panicked>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(Ok(result)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(Panicked)),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! The time-limited trial.
+//!
+//! The first-run date is kept in the config store and mirrored to a marker in
+//! the data store. Deleting the config directory therefore does not restart
+//! the trial: the marker still holds the original date.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::store::{Store, StoreError};
+
+pub const TRIAL_DAYS: i64 = 14;
+
+const TRIAL_FILE: &str = "trial.json";
+const MARKER_FILE: &str = ".cv-install";
+
+#[derive(Serialize, Deserialize)]
+struct TrialRecord {
+    first_run: NaiveDate,
+}
+
+pub struct Trial {
+    pub first_run: NaiveDate,
+    /// One of the two stored copies was deleted or modified.
+    pub reset_detected: bool,
+}
+
+impl Trial {
+    /// Loads the trial state, starting a new trial on a genuine first run.
+    pub fn load_or_start(config: Option<&Store>, data: Option<&Store>, today: NaiveDate) -> Self {
+        let (record, record_tampered) = read(config, TRIAL_FILE);
+        let (marker, marker_tampered) = read(data, MARKER_FILE);
+
+        let (first_run, reset_detected) = match (record, marker) {
+            (Some(record), Some(marker)) => (record.min(marker), false),
+            (Some(record), None) => (record, marker_tampered),
+            (None, Some(marker)) => (marker, true),
+            // Neither copy verifies and at least one was modified: there is
+            // no trustworthy date, so treat the trial as used up.
+            (None, None) if record_tampered || marker_tampered => {
+                (today - chrono::Duration::days(TRIAL_DAYS), true)
+            }
+            (None, None) => (today, false),
+        };
+
+        let record = TrialRecord { first_run };
+        if let Some(store) = config {
+            let _ = store.save(TRIAL_FILE, &record);
+        }
+        if let Some(store) = data {
+            let _ = store.save(MARKER_FILE, &record);
+        }
+        Self { first_run, reset_detected }
+    }
+
+    pub fn days_remaining(&self, today: NaiveDate) -> i64 {
+        (TRIAL_DAYS - (today - self.first_run).num_days()).max(0)
+    }
+
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.days_remaining(today) == 0
+    }
+}
+
+/// Returns the stored first-run date and whether a file existed but failed
+/// verification.
+fn read(store: Option<&Store>, name: &str) -> (Option<NaiveDate>, bool) {
+    let Some(store) = store else { return (None, false) };
+    match store.load::<TrialRecord>(name) {
+        Ok(record) => (record.map(|r| r.first_run), false),
+        Err(StoreError::Tampered) => (None, true),
+        Err(StoreError::Io(_)) => (None, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// A config and a data store in their own temporary directories.
+    fn stores() -> (TempDir, Store, TempDir, Store) {
+        let config_dir = tempfile::tempdir().unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let config = Store::in_dir(config_dir.path().to_path_buf());
+        let data = Store::in_dir(data_dir.path().to_path_buf());
+        (config_dir, config, data_dir, data)
+    }
+
+    fn tamper(dir: &TempDir, name: &str) {
+        let path = dir.path().join(name);
+        let text = fs::read_to_string(&path).unwrap().replace("2025", "2030");
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn expires_after_the_trial_days() {
+        let trial = Trial { first_run: date(2025, 3, 1), reset_detected: false };
+        assert_eq!(trial.days_remaining(date(2025, 3, 1)), TRIAL_DAYS);
+        assert_eq!(trial.days_remaining(date(2025, 3, 14)), 1);
+        assert!(!trial.is_expired(date(2025, 3, 14)));
+        assert!(trial.is_expired(date(2025, 3, 15)));
+        assert!(trial.is_expired(date(2026, 1, 1)));
+    }
+
+    #[test]
+    fn first_run_is_kept_across_launches() {
+        let (_config_dir, config, _data_dir, data) = stores();
+        let first = Trial::load_or_start(Some(&config), Some(&data), date(2025, 3, 1));
+        assert_eq!(first.first_run, date(2025, 3, 1));
+        assert!(!first.reset_detected);
+        let later = Trial::load_or_start(Some(&config), Some(&data), date(2025, 3, 20));
+        assert_eq!(later.first_run, date(2025, 3, 1));
+        assert!(!later.reset_detected);
+    }
+
+    #[test]
+    fn marker_outlives_a_deleted_config() {
+        let (_config_dir, config, _data_dir, data) = stores();
+        Trial::load_or_start(Some(&config), Some(&data), date(2025, 3, 1));
+        config.remove(TRIAL_FILE).unwrap();
+        let trial = Trial::load_or_start(Some(&config), Some(&data), date(2025, 4, 1));
+        assert_eq!(trial.first_run, date(2025, 3, 1));
+        assert!(trial.reset_detected);
+        // The record is restored from the marker.
+        let again = Trial::load_or_start(Some(&config), Some(&data), date(2025, 4, 1));
+        assert!(!again.reset_detected);
+    }
+
+    #[test]
+    fn tampered_copies_are_detected() {
+        let (_config_dir, config, data_dir, data) = stores();
+        Trial::load_or_start(Some(&config), Some(&data), date(2025, 3, 1));
+        tamper(&data_dir, MARKER_FILE);
+        let trial = Trial::load_or_start(Some(&config), Some(&data), date(2025, 3, 5));
+        assert_eq!(trial.first_run, date(2025, 3, 1));
+        assert!(trial.reset_detected);
+    }
+
+    #[test]
+    fn no_trustworthy_date_ends_the_trial() {
+        let (config_dir, config, data_dir, data) = stores();
+        Trial::load_or_start(Some(&config), Some(&data), date(2025, 3, 1));
+        tamper(&config_dir, TRIAL_FILE);
+        tamper(&data_dir, MARKER_FILE);
+        let trial = Trial::load_or_start(Some(&config), Some(&data), date(2025, 3, 5));
+        assert!(trial.reset_detected);
+        assert!(trial.is_expired(date(2025, 3, 5)));
+    }
+
+    #[test]
+    fn earlier_of_the_two_dates_wins() {
+        let (_config_dir, config, _data_dir, data) = stores();
+        config.save(TRIAL_FILE, &TrialRecord { first_run: date(2025, 3, 10) }).unwrap();
+        data.save(MARKER_FILE, &TrialRecord { first_run: date(2025, 3, 1) }).unwrap();
+        let trial = Trial::load_or_start(Some(&config), Some(&data), date(2025, 3, 12));
+        assert_eq!(trial.first_run, date(2025, 3, 1));
+    }
+}
@@ -0,0 +1,269 @@
+//! Reference implementation of the online activation service, for testing the
+//! app against localhost. See `license_core::protocol` for the wire format.
+//!
+//! ```text
+//! cargo run -p license_tools --bin activation_server -- --seats 2 --state activations.json
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::Local;
+use clap::Parser;
+use ed25519_dalek::SigningKey;
+use license_core::protocol::{
+    ActivateRequest, ActivateResponse, DeactivateRequest, DeactivateResponse, HeartbeatRequest,
+    HeartbeatResponse, RevocationListRequest, RevocationListResponse, RevocationRequest,
+    RevocationResponse,
+};
+use license_core::{Claims, RevocationList, SignedLicense, SignedRevocationList};
+use license_tools::http::{read_json, respond, respond_error};
+use license_tools::{DEFAULT_SIGNING_KEY, load_signing_key};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tiny_http::{Method, Request, Server};
+
+#[derive(Parser)]
+#[command(about = "Run the reference activation server")]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1:7878")]
+    bind: String,
+    /// Machines each product key may be activated on at once
+    #[arg(long, default_value_t = 1)]
+    seats: u32,
+    /// JSON file to persist activations in; in-memory if omitted
+    #[arg(long)]
+    state: Option<PathBuf>,
+    /// License ID to report as revoked (repeatable)
+    #[arg(long = "revoke")]
+    revoked: Vec<String>,
+    /// Product key to revoke (repeatable); only its hash is kept
+    #[arg(long = "revoke-key")]
+    revoked_keys: Vec<String>,
+    #[arg(long, default_value = DEFAULT_SIGNING_KEY)]
+    signing_key: PathBuf,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct State {
+    activations: Vec<Activation>,
+    revoked: Vec<String>,
+    /// [`RevocationList::hash_key`] of revoked product keys.
+    #[serde(default)]
+    revoked_keys: Vec<String>,
+}
+
+impl State {
+    /// Whether `license_id` was revoked, by ID or through the product key one
+    /// of its activations was made with.
+    fn is_revoked(&self, license_id: &str) -> bool {
+        self.revoked.iter().any(|id| id == license_id)
+            || self
+                .activations
+                .iter()
+                .any(|a| a.license_id == license_id && self.revoked_keys.contains(&a.key_hash))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Activation {
+    id: String,
+    /// SHA-256 of the normalized product key, the same form as
+    /// [`RevocationList::hash_key`]; keys themselves are never stored.
+    key_hash: String,
+    license_id: String,
+    machine: String,
+    license: SignedLicense,
+}
+
+struct ActivationServer {
+    state: State,
+    state_path: Option<PathBuf>,
+    seats: u32,
+    signing_key: SigningKey,
+    /// Signed copy of the revoked keys and IDs, served at `/v1/revocations`.
+    revocations: SignedRevocationList,
+    revocations_serial: u64,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let signing_key = match load_signing_key(&args.signing_key) {
+        Ok(key) => key,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut state: State = args
+        .state
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    state.revoked.extend(args.revoked);
+    state.revoked_keys.extend(args.revoked_keys.iter().map(|key| RevocationList::hash_key(key)));
+    let list = RevocationList {
+        serial: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        issued: Some(Local::now().date_naive()),
+        keys: state.revoked_keys.clone(),
+        licenses: state.revoked.clone(),
+    };
+    let revocations = SignedRevocationList::sign(&list, &signing_key);
+
+    let server = match Server::http(&args.bind) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("error: cannot listen on {}: {}", args.bind, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("activation server listening on http://{}", args.bind);
+
+    let mut app = ActivationServer {
+        state,
+        state_path: args.state,
+        seats: args.seats,
+        signing_key,
+        revocations,
+        revocations_serial: list.serial,
+    };
+    for request in server.incoming_requests() {
+        app.handle(request);
+    }
+    ExitCode::SUCCESS
+}
+
+impl ActivationServer {
+    fn handle(&mut self, mut request: Request) {
+        if *request.method() != Method::Post {
+            return respond_error(request, 405, "only POST is supported");
+        }
+        let url = request.url().to_string();
+        println!("POST {}", url);
+        match url.as_str() {
+            "/v1/activate" => match read_json(&mut request) {
+                Ok(body) => self.activate(request, body),
+                Err(err) => respond_error(request, 400, &err),
+            },
+            "/v1/deactivate" => match read_json(&mut request) {
+                Ok(body) => self.deactivate(request, body),
+                Err(err) => respond_error(request, 400, &err),
+            },
+            "/v1/heartbeat" => match read_json(&mut request) {
+                Ok(body) => self.heartbeat(request, body),
+                Err(err) => respond_error(request, 400, &err),
+            },
+            "/v1/revocation" => match read_json::<RevocationRequest>(&mut request) {
+                Ok(body) => {
+                    let revoked = self.state.is_revoked(&body.license_id);
+                    respond(request, 200, &RevocationResponse { revoked });
+                }
+                Err(err) => respond_error(request, 400, &err),
+            },
+            "/v1/revocations" => match read_json::<RevocationListRequest>(&mut request) {
+                Ok(body) => {
+                    let list = (body.since_serial < self.revocations_serial).then(|| self.revocations.clone());
+                    respond(request, 200, &RevocationListResponse { list });
+                }
+                Err(err) => respond_error(request, 400, &err),
+            },
+            _ => respond_error(request, 404, "unknown endpoint"),
+        }
+    }
+
+    fn activate(&mut self, request: Request, body: ActivateRequest) {
+        let edition = match license_core::verify_key(&body.key) {
+            Ok(license) => license.edition(),
+            Err(err) => return respond_error(request, 400, &err.to_string()),
+        };
+        let key_hash = RevocationList::hash_key(&body.key);
+        let license_id = format!("CV-ONL-{}", &key_hash[..12].to_ascii_uppercase());
+        if self.state.revoked.contains(&license_id) || self.state.revoked_keys.contains(&key_hash) {
+            return respond_error(request, 403, "License has been revoked");
+        }
+
+        let existing = self.state.activations.iter().filter(|a| a.key_hash == key_hash);
+        if let Some(activation) = existing.clone().find(|a| a.machine == body.machine) {
+            let response = ActivateResponse {
+                activation_id: activation.id.clone(),
+                license: activation.license.clone(),
+            };
+            return respond(request, 200, &response);
+        }
+        if existing.count() >= self.seats as usize {
+            let message = format!("All {} activations for this key are in use", self.seats);
+            return respond_error(request, 409, &message);
+        }
+
+        let claims = Claims {
+            license_id: license_id.clone(),
+            licensee: "Registered User".to_string(),
+            edition,
+            issued: Local::now().date_naive(),
+            expires: None,
+            seats: self.seats,
+            features: Vec::new(),
+            machine: Some(body.machine.clone()),
+            grace_days: None,
+        };
+        let license = SignedLicense::sign(&claims, &self.signing_key);
+        let activation = Activation {
+            id: new_activation_id(&key_hash, &body.machine),
+            key_hash,
+            license_id,
+            machine: body.machine,
+            license: license.clone(),
+        };
+        let response = ActivateResponse { activation_id: activation.id.clone(), license };
+        self.state.activations.push(activation);
+        self.save();
+        respond(request, 200, &response);
+    }
+
+    fn deactivate(&mut self, request: Request, body: DeactivateRequest) {
+        let before = self.state.activations.len();
+        self.state
+            .activations
+            .retain(|a| !(a.id == body.activation_id && a.machine == body.machine));
+        let released = self.state.activations.len() < before;
+        if !released {
+            return respond_error(request, 404, "Activation not found");
+        }
+        self.save();
+        respond(request, 200, &DeactivateResponse { released });
+    }
+
+    fn heartbeat(&mut self, request: Request, body: HeartbeatRequest) {
+        let activation = self
+            .state
+            .activations
+            .iter()
+            .find(|a| a.id == body.activation_id && a.machine == body.machine);
+        let response = match activation {
+            None => HeartbeatResponse { valid: false, message: Some("Activation not found".to_string()) },
+            Some(a) if self.state.is_revoked(&a.license_id) => {
+                HeartbeatResponse { valid: false, message: Some("License has been revoked".to_string()) }
+            }
+            Some(_) => HeartbeatResponse { valid: true, message: None },
+        };
+        respond(request, 200, &response);
+    }
+
+    fn save(&self) {
+        if let Some(path) = &self.state_path {
+            let text = serde_json::to_string_pretty(&self.state).expect("state serializes to JSON");
+            if let Err(err) = fs::write(path, text) {
+                eprintln!("warning: cannot write {}: {}", path.display(), err);
+            }
+        }
+    }
+}
+
+fn new_activation_id(key_hash: &str, machine: &str) -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let digest = Sha256::digest(format!("{}:{}:{}", key_hash, machine, nanos));
+    hex::encode(&digest[..8])
+}
@@ -0,0 +1,315 @@
+//! Command-line reader and writer for `.cvault` files, the container the
+//! app's Encryption tab produces (specified in `docs/cvault-format.md`).
+//!
+//! ```text
+//! cargo run -p license_tools --bin cvault -- inspect report.pdf.cvault
+//! cargo run -p license_tools --bin cvault -- decrypt report.pdf.cvault report.pdf --passphrase 's3cret'
+//!
+//! # Regenerate or check the published test vectors
+//! cargo run -p license_tools --bin cvault -- vectors --out docs/cvault-vectors.json
+//! cargo run -p license_tools --bin cvault -- check-vectors docs/cvault-vectors.json
+//! ```
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use license_core::vault::{self, HEADER_LEN, Header, KdfParams, VaultError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Parser)]
+#[command(about = "Encrypt, decrypt and inspect .cvault files")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Encrypt a file with default parameters
+    Encrypt {
+        input: PathBuf,
+        output: PathBuf,
+        #[arg(long)]
+        passphrase: String,
+    },
+    /// Decrypt a .cvault file
+    Decrypt {
+        input: PathBuf,
+        output: PathBuf,
+        #[arg(long)]
+        passphrase: String,
+    },
+    /// Print the header of a .cvault file
+    Inspect { input: PathBuf },
+    /// Write the test vectors
+    Vectors {
+        #[arg(long, default_value = "docs/cvault-vectors.json")]
+        out: PathBuf,
+    },
+    /// Check this implementation against a test vector file
+    CheckVectors {
+        #[arg(default_value = "docs/cvault-vectors.json")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Vectors {
+    format: String,
+    version: u8,
+    valid: Vec<Valid>,
+    invalid: Vec<Invalid>,
+}
+
+/// A file that decrypts to `plaintext`. `key` is the Argon2id output and
+/// `file` the complete container, so readers can check both steps.
+#[derive(Serialize, Deserialize)]
+struct Valid {
+    name: String,
+    passphrase: String,
+    memory: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+    nonce_prefix: String,
+    chunk_size: u32,
+    key: String,
+    plaintext: String,
+    file: String,
+}
+
+/// A file a reader must reject, and the error it must report.
+#[derive(Serialize, Deserialize)]
+struct Invalid {
+    name: String,
+    passphrase: String,
+    file: String,
+    error: String,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let result = match args.command {
+        Command::Encrypt { input, output, passphrase } => convert(&input, &output, |reader, writer| {
+            vault::encrypt(reader, writer, &passphrase, &Header::generate(), |_| {})
+        }),
+        Command::Decrypt { input, output, passphrase } => {
+            convert(&input, &output, |reader, writer| vault::decrypt(reader, writer, &passphrase, |_| {}))
+        }
+        Command::Inspect { input } => inspect(&input),
+        Command::Vectors { out } => write_vectors(&out),
+        Command::CheckVectors { path } => check_vectors(&path),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Streams `input` through `run` into a new `output`, which is removed
+/// again if `run` fails part way.
+fn convert<F>(input: &Path, output: &Path, run: F) -> Result<(), String>
+where
+    F: FnOnce(&mut BufReader<File>, &mut BufWriter<File>) -> Result<u64, VaultError>,
+{
+    let file = File::open(input).map_err(|e| format!("cannot read {}: {}", input.display(), e))?;
+    let out = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(output)
+        .map_err(|e| format!("cannot create {}: {}", output.display(), e))?;
+    let mut writer = BufWriter::new(out);
+    let result = run(&mut BufReader::new(file), &mut writer)
+        .map_err(|e| format!("{}: {}", input.display(), e))
+        .and_then(|len| writer.flush().map(|()| len).map_err(|e| format!("cannot write {}: {}", output.display(), e)));
+    match result {
+        Ok(len) => {
+            println!("{} -> {} ({} bytes of plaintext)", input.display(), output.display(), len);
+            Ok(())
+        }
+        Err(err) => {
+            drop(writer);
+            let _ = fs::remove_file(output);
+            Err(err)
+        }
+    }
+}
+
+fn inspect(input: &Path) -> Result<(), String> {
+    let bytes = fs::read(input).map_err(|e| format!("cannot read {}: {}", input.display(), e))?;
+    let header_bytes: &[u8; HEADER_LEN] = bytes
+        .get(..HEADER_LEN)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| format!("{}: {}", input.display(), VaultError::Truncated))?;
+    let header = Header::parse(header_bytes).map_err(|e| format!("{}: {}", input.display(), e))?;
+    let body = (bytes.len() - HEADER_LEN) as u64;
+    let sealed = header.chunk_size as u64 + vault::TAG_LEN as u64;
+    let chunks = body.div_ceil(sealed).max(1);
+    println!("version:      {}", vault::VERSION);
+    println!("cipher:       AES-256-GCM (STREAM, 32-bit big-endian counter)");
+    println!("kdf:          Argon2id v1.3");
+    println!("memory:       {} KiB", header.kdf.memory);
+    println!("iterations:   {}", header.kdf.iterations);
+    println!("parallelism:  {}", header.kdf.parallelism);
+    println!("salt:         {}", hex::encode(header.salt));
+    println!("nonce prefix: {}", hex::encode(header.nonce_prefix));
+    println!("chunk size:   {} bytes", header.chunk_size);
+    println!("chunks:       {}", chunks);
+    println!("plaintext:    {} bytes", body.saturating_sub(chunks * vault::TAG_LEN as u64));
+    Ok(())
+}
+
+/// Small KDF costs keep the vectors quick to check; only the last valid
+/// vector uses the defaults the app writes with.
+const VECTOR_KDF: KdfParams = KdfParams { memory: 64, iterations: 1, parallelism: 1 };
+const VECTOR_SALT: [u8; 16] = *b"cvault-vectors-1";
+const VECTOR_NONCE_PREFIX: [u8; 7] = [0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6];
+const VECTOR_CHUNK_SIZE: u32 = 16;
+const PASSPHRASE: &str = "correct horse battery staple";
+
+fn write_vectors(out: &Path) -> Result<(), String> {
+    let header = Header { kdf: VECTOR_KDF, salt: VECTOR_SALT, nonce_prefix: VECTOR_NONCE_PREFIX, chunk_size: VECTOR_CHUNK_SIZE };
+    let default_header = Header { kdf: KdfParams::default(), chunk_size: vault::DEFAULT_CHUNK_SIZE, ..header.clone() };
+    let valid = vec![
+        valid("empty", PASSPHRASE, &header, b"")?,
+        valid("single-partial-chunk", PASSPHRASE, &header, b"hello")?,
+        valid("single-full-chunk", PASSPHRASE, &header, b"0123456789abcdef")?,
+        valid("two-full-chunks", PASSPHRASE, &header, b"0123456789abcdef0123456789ABCDEF")?,
+        valid("three-chunks", PASSPHRASE, &header, b"The quick brown fox jumps over the lazy dog")?,
+        valid("utf8-passphrase", "p\u{e4}ssw\u{f6}rd \u{1F511}", &header, b"non-ASCII passphrases are hashed as UTF-8")?,
+        valid("default-parameters", PASSPHRASE, &default_header, b"Written with the parameters the app uses.")?,
+    ];
+
+    let base = hex::decode(&valid[4].file).expect("generated hex");
+    let body_start = HEADER_LEN;
+    let mut invalid = Vec::new();
+    let mut add = |name: &str, passphrase: &str, file: Vec<u8>, error: &str| {
+        invalid.push(Invalid { name: name.to_string(), passphrase: passphrase.to_string(), file: hex::encode(file), error: error.to_string() });
+    };
+    let modified = |at: usize, value: u8| {
+        let mut file = base.clone();
+        file[at] = value;
+        file
+    };
+    add("wrong-passphrase", "Correct horse battery staple", base.clone(), "authentication");
+    add("bad-magic", PASSPHRASE, modified(0, b'X'), "not_a_vault");
+    add("unsupported-version", PASSPHRASE, modified(6, 2), "unsupported_version");
+    add("unsupported-cipher", PASSPHRASE, modified(7, 2), "unsupported_cipher");
+    add("unsupported-kdf", PASSPHRASE, modified(8, 2), "unsupported_kdf");
+    add("zero-chunk-size", PASSPHRASE, [&base[..44], &[0, 0, 0, 0], &base[48..]].concat(), "bad_header");
+    add("excessive-iterations", PASSPHRASE, [&base[..13], &u32::MAX.to_le_bytes(), &base[17..]].concat(), "bad_header");
+    add("excessive-parallelism", PASSPHRASE, [&base[..17], &u32::MAX.to_le_bytes(), &base[21..]].concat(), "bad_header");
+    add("modified-salt", PASSPHRASE, modified(21, base[21] ^ 1), "authentication");
+    add("modified-nonce-prefix", PASSPHRASE, modified(37, base[37] ^ 1), "authentication");
+    add("modified-ciphertext", PASSPHRASE, modified(body_start + 20, base[body_start + 20] ^ 1), "authentication");
+    add("modified-tag", PASSPHRASE, modified(base.len() - 1, base[base.len() - 1] ^ 1), "authentication");
+    add("last-chunk-dropped", PASSPHRASE, base[..body_start + 64].to_vec(), "authentication");
+    add("chunks-swapped", PASSPHRASE, [&base[..body_start], &base[body_start + 32..body_start + 64], &base[body_start..body_start + 32], &base[body_start + 64..]].concat(), "authentication");
+    add("trailing-data", PASSPHRASE, [&base[..], &[0u8; 16][..]].concat(), "authentication");
+    add("header-only", PASSPHRASE, base[..body_start].to_vec(), "truncated");
+    add("short-last-chunk", PASSPHRASE, base[..body_start + 64 + 8].to_vec(), "truncated");
+    add("short-header", PASSPHRASE, base[..20].to_vec(), "truncated");
+
+    let vectors = Vectors { format: "cvault".to_string(), version: vault::VERSION, valid, invalid };
+    let json = serde_json::to_string_pretty(&vectors).map_err(|e| e.to_string())?;
+    fs::write(out, json + "\n").map_err(|e| format!("cannot write {}: {}", out.display(), e))?;
+    println!("{}: {} valid, {} invalid vectors", out.display(), vectors.valid.len(), vectors.invalid.len());
+    Ok(())
+}
+
+fn valid(name: &str, passphrase: &str, header: &Header, plaintext: &[u8]) -> Result<Valid, String> {
+    let key = header.derive_key(passphrase).map_err(|e| e.to_string())?;
+    let mut file = Vec::new();
+    vault::encrypt(&mut &plaintext[..], &mut file, passphrase, header, |_| {}).map_err(|e| e.to_string())?;
+    Ok(Valid {
+        name: name.to_string(),
+        passphrase: passphrase.to_string(),
+        memory: header.kdf.memory,
+        iterations: header.kdf.iterations,
+        parallelism: header.kdf.parallelism,
+        salt: hex::encode(header.salt),
+        nonce_prefix: hex::encode(header.nonce_prefix),
+        chunk_size: header.chunk_size,
+        key: hex::encode(key),
+        plaintext: hex::encode(plaintext),
+        file: hex::encode(file),
+    })
+}
+
+fn check_vectors(path: &Path) -> Result<(), String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let vectors: Vectors = serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut failures = 0;
+    for vector in &vectors.valid {
+        let outcome = check_valid(vector);
+        failures += report(&vector.name, &outcome);
+    }
+    for vector in &vectors.invalid {
+        let outcome = check_invalid(vector);
+        failures += report(&vector.name, &outcome);
+    }
+    if failures > 0 {
+        return Err(format!("{} of {} vectors failed", failures, vectors.valid.len() + vectors.invalid.len()));
+    }
+    Ok(())
+}
+
+fn check_valid(vector: &Valid) -> Result<(), String> {
+    let decode = |field: &str, value: &str| hex::decode(value).map_err(|e| format!("{}: {}", field, e));
+    let file = decode("file", &vector.file)?;
+    let plaintext = decode("plaintext", &vector.plaintext)?;
+    let header_bytes: &[u8; HEADER_LEN] = file.get(..HEADER_LEN).and_then(|b| b.try_into().ok()).ok_or("file: too short")?;
+    let header = Header::parse(header_bytes).map_err(|e| format!("header: {}", e))?;
+    let expected = Header {
+        kdf: KdfParams { memory: vector.memory, iterations: vector.iterations, parallelism: vector.parallelism },
+        salt: decode("salt", &vector.salt)?.try_into().map_err(|_| "salt: not 16 bytes")?,
+        nonce_prefix: decode("nonce_prefix", &vector.nonce_prefix)?.try_into().map_err(|_| "nonce_prefix: not 7 bytes")?,
+        chunk_size: vector.chunk_size,
+    };
+    if header != expected {
+        return Err("header does not match the listed parameters".to_string());
+    }
+    let key = header.derive_key(&vector.passphrase).map_err(|e| e.to_string())?;
+    if hex::encode(key) != vector.key {
+        return Err("derived key differs".to_string());
+    }
+    let mut decrypted = Vec::new();
+    vault::decrypt(&mut &file[..], &mut decrypted, &vector.passphrase, |_| {}).map_err(|e| format!("decrypt: {}", e))?;
+    if decrypted != plaintext {
+        return Err("decrypted plaintext differs".to_string());
+    }
+    let mut encrypted = Vec::new();
+    vault::encrypt(&mut &plaintext[..], &mut encrypted, &vector.passphrase, &header, |_| {}).map_err(|e| format!("encrypt: {}", e))?;
+    if encrypted != file {
+        return Err("re-encrypted file differs".to_string());
+    }
+    Ok(())
+}
+
+fn check_invalid(vector: &Invalid) -> Result<(), String> {
+    let file = hex::decode(&vector.file).map_err(|e| format!("file: {}", e))?;
+    match vault::decrypt(&mut &file[..], &mut Vec::new(), &vector.passphrase, |_| {}) {
+        Ok(_) => Err(format!("accepted, expected {}", vector.error)),
+        Err(err) if err.code() == vector.error => Ok(()),
+        Err(err) => Err(format!("got {} ({}), expected {}", err.code(), err, vector.error)),
+    }
+}
+
+fn report(name: &str, outcome: &Result<(), String>) -> usize {
+    match outcome {
+        Ok(()) => {
+            println!("ok      {}", name);
+            0
+        }
+        Err(err) => {
+            println!("FAILED  {}: {}", name, err);
+            1
+        }
+    }
+}
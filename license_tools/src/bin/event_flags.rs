@@ -0,0 +1,148 @@
+//! Organizer tool for per-player flags at CTF events.
+//!
+//! ```text
+//! # Challenge config shipped next to the app; the secret goes in sealed
+//! cargo run -p license_tools --bin event_flags -- config --event ctf-2026 --secret s3cret
+//!
+//! # Check submissions (`player_id,flag` per line) against the roster
+//! cargo run -p license_tools --bin event_flags -- verify --event ctf-2026 --secret s3cret \
+//!     --config challenge.json --roster roster.txt --submissions submissions.csv
+//! ```
+//!
+//! The roster lists one player ID per line; blank lines and lines starting
+//! with `#` are ignored. A flag also encodes how many hints its player
+//! revealed, so verifying reports each player's score under the config's
+//! scoring, or the built-in one without `--config`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use license_core::{ChallengeConfig, Scoring, normalize_player_id, player_flag};
+
+#[derive(Parser)]
+#[command(about = "Issue and verify per-player challenge flags")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Write the challenge config for an event
+    Config {
+        #[arg(long)]
+        event: String,
+        #[arg(long)]
+        secret: String,
+        #[arg(long, default_value = "challenge.json")]
+        out: PathBuf,
+    },
+    /// Print the flag of one player
+    Flag {
+        #[arg(long)]
+        event: String,
+        #[arg(long)]
+        secret: String,
+        #[arg(long)]
+        player: String,
+        /// Hints the player revealed
+        #[arg(long, default_value_t = 0)]
+        hints: usize,
+    },
+    /// Check submitted flags against the player roster
+    Verify {
+        #[arg(long)]
+        event: String,
+        #[arg(long)]
+        secret: String,
+        /// Challenge config whose scoring the flags were earned under
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long)]
+        roster: PathBuf,
+        /// `player_id,flag` per line
+        #[arg(long)]
+        submissions: PathBuf,
+    },
+}
+
+fn main() -> ExitCode {
+    let result = match Args::parse().command {
+        Command::Config { event, secret, out } => fs::write(&out, ChallengeConfig::new(&event, &secret).to_json())
+            .map(|()| println!("{}", out.display()))
+            .map_err(|e| format!("cannot write {}: {}", out.display(), e)),
+        Command::Flag { event, secret, player, hints } => {
+            println!("{}", player_flag(&secret, &event, &player, hints));
+            Ok(())
+        }
+        Command::Verify { event, secret, config, roster, submissions } => {
+            load_scoring(config.as_deref()).and_then(|scoring| verify(&event, &secret, &scoring, &roster, &submissions))
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn load_scoring(config: Option<&Path>) -> Result<Scoring, String> {
+    let Some(path) = config else { return Ok(Scoring::default()) };
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let config = ChallengeConfig::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(config.scoring)
+}
+
+/// Prints one verdict per submission, with the score for accepted flags. A
+/// flag that belongs to another rostered player is reported as shared.
+fn verify(event: &str, secret: &str, scoring: &Scoring, roster: &Path, submissions: &Path) -> Result<(), String> {
+    let players: Vec<String> = read_lines(roster)?.iter().map(|id| normalize_player_id(id)).collect();
+    let owners: HashMap<String, (&str, usize)> = players
+        .iter()
+        .flat_map(|id| (0..=scoring.hints.len()).map(move |hints| (player_flag(secret, event, id, hints), (id.as_str(), hints))))
+        .collect();
+
+    let mut rejected = 0;
+    for line in read_lines(submissions)? {
+        let Some((player, flag)) = line.split_once(',') else {
+            println!("MALFORMED  {}", line);
+            rejected += 1;
+            continue;
+        };
+        let player = normalize_player_id(player);
+        let flag = flag.trim();
+        let (verdict, score) = match owners.get(flag) {
+            _ if !players.contains(&player) => ("UNKNOWN PLAYER".to_string(), String::new()),
+            Some(&(owner, hints)) if owner == player => {
+                ("OK".to_string(), format!(" ({} hint(s), score {})", hints, scoring.score(hints)))
+            }
+            Some((owner, _)) => (format!("SHARED (flag of {})", owner), String::new()),
+            None => ("WRONG".to_string(), String::new()),
+        };
+        if verdict != "OK" {
+            rejected += 1;
+        }
+        println!("{:<10} {}{}", verdict, player, score);
+    }
+
+    if rejected > 0 {
+        Err(format!("{} submission(s) rejected", rejected))
+    } else {
+        Ok(())
+    }
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
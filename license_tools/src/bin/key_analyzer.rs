@@ -0,0 +1,262 @@
+//! Solution-space analysis of the `XXXX-XXXX-XXXX` key scheme for challenge
+//! authors: how many keys reach a target, how many of them the `TEST`/`AAAA`
+//! blacklist removes, and how many easy-looking keys get through.
+//!
+//! ```text
+//! cargo run --release -p license_tools --bin key_analyzer -- --charset 0123456789ABCDEF
+//! cargo run --release -p license_tools --bin key_analyzer -- \
+//!     --target 0x85FD063D --target 0xDEADBEEF --out packs/key_analysis.json
+//! ```
+//!
+//! A key is split into a first half `XXXX-XX` and a second half `XX-XXXX`.
+//! Since [`mix`](license_core::mix) is a bijection on the state for a fixed
+//! byte, second halves can be unhashed back from the target; a key is valid
+//! exactly when its first half hashes forward from the seed to the same state.
+//! When every half fits in `--exact-limit` the search enumerates both sides
+//! and the counts are exact. Otherwise random halves are sampled from both
+//! sides and the matches are scaled up to the whole key space.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use license_core::{KEY_SEED, KEY_TARGET, blocked_pattern, key_hash, key_unhash};
+use license_tools::{DEFAULT_CHARSET, parse_charset, parse_u32, random_half};
+use serde::Serialize;
+
+#[derive(Parser)]
+#[command(about = "Count and sample the valid legacy keys for candidate targets")]
+struct Args {
+    /// Initial hash state
+    #[arg(long, value_parser = parse_u32, default_value_t = KEY_SEED)]
+    seed: u32,
+    /// Candidate final state (repeatable); defaults to the app's target
+    #[arg(long = "target", value_parser = parse_u32)]
+    targets: Vec<u32>,
+    /// Characters keys are drawn from
+    #[arg(long, default_value = DEFAULT_CHARSET)]
+    charset: String,
+    /// Largest number of halves enumerated for an exact count; each takes
+    /// 8 bytes of memory
+    #[arg(long, default_value_t = 1 << 24)]
+    exact_limit: u64,
+    /// Halves sampled from each side when the count is estimated
+    #[arg(long, default_value_t = 1 << 22)]
+    sample_size: usize,
+    /// Example keys listed per target
+    #[arg(long, default_value_t = 10)]
+    samples: usize,
+    /// Makes sampled reports reproducible
+    #[arg(long)]
+    rng_seed: Option<u64>,
+    /// Write the report here instead of printing it
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    seed: String,
+    charset: String,
+    /// `exact`, or `sampled` when the counts are estimates.
+    method: &'static str,
+    key_space: f64,
+    /// Valid keys per target if the hash spread keys evenly over all states.
+    expected_per_target: f64,
+    targets: Vec<TargetReport>,
+}
+
+#[derive(Serialize)]
+struct TargetReport {
+    target: String,
+    /// Keys that hash to the target, before the blacklist.
+    valid_keys: u64,
+    /// `valid_keys` relative to `expected_per_target`; far from 1 means the
+    /// target is unusually easy or hard to hit with this charset.
+    ratio: f64,
+    /// Valid keys the blacklist rejects, by pattern.
+    blocked: BTreeMap<&'static str, u64>,
+    /// Valid keys that `verify_key` accepts.
+    accepted_keys: u64,
+    /// Accepted keys that are easy to guess or spot.
+    weak_keys: u64,
+    /// Matching half pairs behind the estimates of a sampled report.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches_sampled: Option<u64>,
+    examples: Vec<String>,
+    weak_examples: Vec<String>,
+}
+
+/// Valid keys found for one target.
+#[derive(Default)]
+struct Tally {
+    valid: u64,
+    blocked: BTreeMap<&'static str, u64>,
+    weak: u64,
+    examples: Vec<String>,
+    weak_examples: Vec<String>,
+}
+
+impl Tally {
+    fn add(&mut self, first: &[u8; 7], second: &[u8; 7], max_examples: usize) {
+        let key: String = first.iter().chain(second).map(|&b| b as char).collect();
+        self.valid += 1;
+        if let Some(pattern) = blocked_pattern(&key) {
+            *self.blocked.entry(pattern).or_default() += 1;
+        } else if is_weak(&key) {
+            self.weak += 1;
+            if self.weak_examples.len() < max_examples {
+                self.weak_examples.push(key);
+            }
+        } else if self.examples.len() < max_examples {
+            self.examples.push(key);
+        }
+    }
+
+    fn into_report(self, target: u32, scale: f64, expected: f64, matches_sampled: Option<u64>) -> TargetReport {
+        let scaled = |count: u64| (count as f64 * scale).round() as u64;
+        let blocked_total: u64 = self.blocked.values().sum();
+        TargetReport {
+            target: format!("0x{:08X}", target),
+            valid_keys: scaled(self.valid),
+            ratio: self.valid as f64 * scale / expected,
+            blocked: self.blocked.into_iter().map(|(pattern, count)| (pattern, scaled(count))).collect(),
+            accepted_keys: scaled(self.valid - blocked_total),
+            weak_keys: scaled(self.weak),
+            matches_sampled,
+            examples: self.examples,
+            weak_examples: self.weak_examples,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(mut args: Args) -> Result<(), String> {
+    let charset = parse_charset(&args.charset)?;
+    if args.targets.is_empty() {
+        args.targets.push(KEY_TARGET);
+    }
+    let halves = (charset.len() as u64).checked_pow(6).unwrap_or(u64::MAX);
+    let key_space = (charset.len() as f64).powi(12);
+    let expected = key_space / 2f64.powi(32);
+
+    let exact = halves <= args.exact_limit.min(u32::MAX as u64);
+    let targets = if exact {
+        analyze_exact(&args, &charset, halves, expected)
+    } else {
+        if args.sample_size == 0 {
+            return Err("--sample-size must be positive".to_string());
+        }
+        analyze_sampled(&args, &charset, halves as f64, expected)
+    };
+
+    let report = Report {
+        seed: format!("0x{:08X}", args.seed),
+        charset: charset.iter().map(|&b| b as char).collect(),
+        method: if exact { "exact" } else { "sampled" },
+        key_space,
+        expected_per_target: expected,
+        targets,
+    };
+    let json = serde_json::to_string_pretty(&report).expect("report serializes to JSON");
+    match &args.out {
+        Some(path) => fs::write(path, json + "\n").map_err(|e| format!("cannot write {}: {}", path.display(), e)),
+        None => {
+            println!("{}", json);
+            Ok(())
+        }
+    }
+}
+
+/// Hashes every first half once, then matches every second half against
+/// them for each target.
+fn analyze_exact(args: &Args, charset: &[u8], halves: u64, expected: f64) -> Vec<TargetReport> {
+    let mut table: Vec<(u32, u32)> =
+        (0..halves).map(|i| (key_hash(args.seed, &nth_half(charset, 4, i)), i as u32)).collect();
+    table.sort_unstable();
+
+    args.targets
+        .iter()
+        .map(|&target| {
+            let mut tally = Tally::default();
+            for j in 0..halves {
+                let second = nth_half(charset, 2, j);
+                for &(_, i) in matching(&table, key_unhash(target, &second)) {
+                    tally.add(&nth_half(charset, 4, i as u64), &second, args.samples);
+                }
+            }
+            tally.into_report(target, 1.0, expected, None)
+        })
+        .collect()
+}
+
+/// Matches random second halves against a table of random first halves and
+/// scales the counts by how much of each side was covered.
+fn analyze_sampled(args: &Args, charset: &[u8], halves: f64, expected: f64) -> Vec<TargetReport> {
+    let mut rng = args.rng_seed.map_or_else(fastrand::Rng::new, fastrand::Rng::with_seed);
+    let n = args.sample_size;
+    let mut table: Vec<(u32, [u8; 7])> = (0..n)
+        .map(|_| {
+            let half = random_half(charset, 4, &mut rng);
+            (key_hash(args.seed, &half), half)
+        })
+        .collect();
+    table.sort_unstable();
+    let scale = (halves / n as f64).powi(2);
+
+    args.targets
+        .iter()
+        .map(|&target| {
+            let mut tally = Tally::default();
+            for _ in 0..n {
+                let second = random_half(charset, 2, &mut rng);
+                for (_, first) in matching(&table, key_unhash(target, &second)) {
+                    tally.add(first, &second, args.samples);
+                }
+            }
+            let matches = tally.valid;
+            tally.into_report(target, scale, expected, Some(matches))
+        })
+        .collect()
+}
+
+/// Entries of the sorted `table` whose state is `state`.
+fn matching<T>(table: &[(u32, T)], state: u32) -> &[(u32, T)] {
+    let start = table.partition_point(|(s, _)| *s < state);
+    let len = table[start..].iter().take_while(|(s, _)| *s == state).count();
+    &table[start..start + len]
+}
+
+/// The `index`-th half in charset order, with a dash at `dash`.
+fn nth_half(charset: &[u8], dash: usize, mut index: u64) -> [u8; 7] {
+    let base = charset.len() as u64;
+    let mut half = [b'-'; 7];
+    for i in (0..7).rev().filter(|&i| i != dash) {
+        half[i] = charset[(index % base) as usize];
+        index /= base;
+    }
+    half
+}
+
+/// Keys a player might guess or a reviewer would frown at: a group of one
+/// repeated character, a group that counts up or down (`ABCD`, `4321`), or
+/// the same group twice.
+fn is_weak(key: &str) -> bool {
+    let groups: Vec<&[u8]> = key.as_bytes().split(|&b| b == b'-').collect();
+    let repeated = |g: &[u8]| g.windows(2).all(|w| w[1] == w[0]);
+    let ascending = |g: &[u8]| g.windows(2).all(|w| w[1] == w[0].wrapping_add(1));
+    let descending = |g: &[u8]| g.windows(2).all(|w| w[1].wrapping_add(1) == w[0]);
+    groups.iter().any(|g| repeated(g) || ascending(g) || descending(g))
+        || (0..groups.len()).any(|i| groups[i + 1..].contains(&groups[i]))
+}
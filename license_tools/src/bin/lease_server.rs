@@ -0,0 +1,191 @@
+//! Reference floating-license server: hands out time-limited leases on a
+//! shared pool of seats. See `license_core::protocol` for the wire format.
+//!
+//! ```text
+//! cargo run -p license_tools --bin lease_server -- --seats 5 --lease-secs 300
+//! CYBERVAULT_LEASE_URL=http://127.0.0.1:7879 cargo run -p license_gate
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{Days, Local};
+use clap::Parser;
+use ed25519_dalek::SigningKey;
+use license_core::protocol::{
+    LeaseRequest, LeaseResponse, ReleaseLeaseRequest, ReleaseLeaseResponse, RenewLeaseRequest,
+    RenewLeaseResponse,
+};
+use license_core::{Claims, Edition, SignedLicense};
+use license_tools::http::{read_json, respond, respond_error};
+use license_tools::{DEFAULT_SIGNING_KEY, load_signing_key};
+use sha2::{Digest, Sha256};
+use tiny_http::{Method, Request, Server};
+
+#[derive(Parser)]
+#[command(about = "Run the reference floating-license server")]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1:7879")]
+    bind: String,
+    /// Machines that may hold a lease at the same time
+    #[arg(long, default_value_t = 5)]
+    seats: u32,
+    /// Lease lifetime; clients renew at half of it
+    #[arg(long, default_value_t = 300)]
+    lease_secs: u64,
+    /// trial, pro or enterprise
+    #[arg(long, default_value = "enterprise")]
+    edition: Edition,
+    #[arg(long, default_value = "Floating License Pool")]
+    licensee: String,
+    #[arg(long, default_value = DEFAULT_SIGNING_KEY)]
+    signing_key: PathBuf,
+}
+
+struct Lease {
+    id: String,
+    machine: String,
+    expires: Instant,
+}
+
+struct LeaseServer {
+    leases: Vec<Lease>,
+    seats: u32,
+    lease_time: Duration,
+    edition: Edition,
+    licensee: String,
+    signing_key: SigningKey,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let signing_key = match load_signing_key(&args.signing_key) {
+        Ok(key) => key,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let server = match Server::http(&args.bind) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("error: cannot listen on {}: {}", args.bind, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("lease server listening on http://{} ({} seats)", args.bind, args.seats);
+
+    let mut app = LeaseServer {
+        leases: Vec::new(),
+        seats: args.seats,
+        lease_time: Duration::from_secs(args.lease_secs.max(10)),
+        edition: args.edition,
+        licensee: args.licensee,
+        signing_key,
+    };
+    for request in server.incoming_requests() {
+        app.handle(request);
+    }
+    ExitCode::SUCCESS
+}
+
+impl LeaseServer {
+    fn handle(&mut self, mut request: Request) {
+        if *request.method() != Method::Post {
+            return respond_error(request, 405, "only POST is supported");
+        }
+        let now = Instant::now();
+        self.leases.retain(|lease| lease.expires > now);
+
+        let url = request.url().to_string();
+        match url.as_str() {
+            "/v1/lease/acquire" => match read_json(&mut request) {
+                Ok(body) => self.acquire(request, body),
+                Err(err) => respond_error(request, 400, &err),
+            },
+            "/v1/lease/renew" => match read_json(&mut request) {
+                Ok(body) => self.renew(request, body),
+                Err(err) => respond_error(request, 400, &err),
+            },
+            "/v1/lease/release" => match read_json(&mut request) {
+                Ok(body) => self.release(request, body),
+                Err(err) => respond_error(request, 400, &err),
+            },
+            _ => respond_error(request, 404, "unknown endpoint"),
+        }
+        println!("POST {} ({}/{} seats in use)", url, self.leases.len(), self.seats);
+    }
+
+    fn acquire(&mut self, request: Request, body: LeaseRequest) {
+        let expires = Instant::now() + self.lease_time;
+        // A client that restarts without releasing gets its old seat back.
+        if let Some(index) = self.leases.iter().position(|l| l.machine == body.machine) {
+            let license = self.issue(&self.leases[index].id, &body.machine);
+            let lease = &mut self.leases[index];
+            lease.expires = expires;
+            let response = LeaseResponse { lease_id: lease.id.clone(), lease_secs: self.lease_time.as_secs(), license };
+            return respond(request, 200, &response);
+        }
+        if self.leases.len() >= self.seats as usize {
+            let message = format!("All {} floating seats are in use", self.seats);
+            return respond_error(request, 409, &message);
+        }
+
+        let id = new_lease_id(&body.machine);
+        let license = self.issue(&id, &body.machine);
+        let response = LeaseResponse {
+            lease_id: id.clone(),
+            lease_secs: self.lease_time.as_secs(),
+            license,
+        };
+        self.leases.push(Lease { id, machine: body.machine, expires });
+        respond(request, 200, &response);
+    }
+
+    fn renew(&mut self, request: Request, body: RenewLeaseRequest) {
+        let expires = Instant::now() + self.lease_time;
+        match self.leases.iter().position(|l| l.id == body.lease_id && l.machine == body.machine) {
+            Some(index) => {
+                let license = self.issue(&body.lease_id, &body.machine);
+                self.leases[index].expires = expires;
+                respond(request, 200, &RenewLeaseResponse { lease_secs: self.lease_time.as_secs(), license });
+            }
+            None => respond_error(request, 404, "Lease not found or expired"),
+        }
+    }
+
+    /// Signs the license for lease `id`, valid until tomorrow. Every acquire
+    /// and renewal issues a new one, so a seat that is kept renewed never
+    /// reaches its expiry.
+    fn issue(&self, id: &str, machine: &str) -> SignedLicense {
+        let today = Local::now().date_naive();
+        let claims = Claims {
+            license_id: format!("CV-FLT-{}", id.to_ascii_uppercase()),
+            licensee: self.licensee.clone(),
+            edition: self.edition,
+            issued: today,
+            // The lease is what keeps the seat; the date only bounds how long
+            // a leaked copy of the license could be replayed.
+            expires: today.checked_add_days(Days::new(1)),
+            seats: self.seats,
+            features: Vec::new(),
+            machine: Some(machine.to_string()),
+            grace_days: Some(0),
+        };
+        SignedLicense::sign(&claims, &self.signing_key)
+    }
+
+    fn release(&mut self, request: Request, body: ReleaseLeaseRequest) {
+        let before = self.leases.len();
+        self.leases.retain(|l| !(l.id == body.lease_id && l.machine == body.machine));
+        let released = self.leases.len() < before;
+        respond(request, 200, &ReleaseLeaseResponse { released });
+    }
+}
+
+fn new_lease_id(machine: &str) -> String {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let digest = Sha256::digest(format!("lease:{}:{}", machine, nanos));
+    hex::encode(&digest[..8])
+}
@@ -0,0 +1,246 @@
+//! Key and license generator for challenge authors and vendors.
+//!
+//! ```text
+//! cargo run -p license_tools --bin license_keygen -- keys --count 5
+//! cargo run -p license_tools --bin license_keygen -- keys --target 0xDEADBEEF --charset 0123456789
+//! cargo run -p license_tools --bin license_keygen -- keys --format v2 --edition pro --count 5
+//! cargo run -p license_tools --bin license_keygen -- licenses --licensee "Example Corp" --edition pro --count 10
+//! cargo run -p license_tools --bin license_keygen -- signing-key
+//! ```
+//!
+//! Legacy keys are found with a meet-in-the-middle search: random first halves
+//! are hashed forward from the seed, random second halves are unhashed back
+//! from the target, and any state the two sides share joins into a valid key.
+//! V2 keys are encoded directly from a random serial.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use chrono::{Duration, Local};
+use clap::{Parser, Subcommand};
+use ed25519_dalek::SigningKey;
+use license_core::{Claims, Edition, KEY_SEED, KEY_TARGET, KeyFormat, SignedLicense};
+use license_tools::{DEFAULT_CHARSET, DEFAULT_SIGNING_KEY, load_signing_key, parse_charset, parse_u32, random_half};
+
+/// Forward states kept in the search table. With 2^18 entries a random second
+/// half matches about once every 2^14 tries.
+const TABLE_SIZE: usize = 1 << 18;
+/// Second halves tried per key before the target is reported unsolvable.
+const MAX_TRIES: u64 = 1 << 28;
+
+#[derive(Parser)]
+#[command(about = "Generate product keys and signed license files")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate product keys
+    Keys {
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// legacy (XXXX-XXXX-XXXX) or v2 (XXXXXXX-XXXXXXX-XXXXXXX)
+        #[arg(long, default_value = "legacy")]
+        format: KeyFormat,
+        /// Edition encoded in V2 keys; legacy keys are always Enterprise
+        #[arg(long, default_value = "enterprise")]
+        edition: Edition,
+        /// Initial hash state (legacy)
+        #[arg(long, value_parser = parse_u32, default_value_t = KEY_SEED)]
+        seed: u32,
+        /// Final hash state a key must reach (legacy)
+        #[arg(long, value_parser = parse_u32, default_value_t = KEY_TARGET)]
+        target: u32,
+        /// Characters keys are drawn from (legacy)
+        #[arg(long, default_value = DEFAULT_CHARSET)]
+        charset: String,
+        /// Makes the output reproducible
+        #[arg(long)]
+        rng_seed: Option<u64>,
+    },
+    /// Issue a batch of signed license files
+    Licenses {
+        /// One set of licenses is issued per licensee (repeatable)
+        #[arg(long = "licensee", required = true)]
+        licensees: Vec<String>,
+        /// Licenses per licensee
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// trial, pro or enterprise
+        #[arg(long, default_value = "pro")]
+        edition: Edition,
+        /// Days until expiry; omit for perpetual licenses
+        #[arg(long)]
+        days: Option<i64>,
+        /// Days the app keeps working after expiry before turning read-only
+        #[arg(long)]
+        grace_days: Option<u32>,
+        #[arg(long, default_value_t = 1)]
+        seats: u32,
+        /// Extra feature to grant beyond the edition (repeatable)
+        #[arg(long = "feature")]
+        features: Vec<String>,
+        /// License IDs are <prefix>-<date>-<n>
+        #[arg(long, default_value = "CV-BATCH")]
+        id_prefix: String,
+        #[arg(long, default_value = "licenses")]
+        out_dir: PathBuf,
+        #[arg(long, default_value = DEFAULT_SIGNING_KEY)]
+        signing_key: PathBuf,
+    },
+    /// Create a vendor signing key; the public key goes next to it as `.pub`
+    SigningKey {
+        #[arg(long, default_value = DEFAULT_SIGNING_KEY)]
+        out: PathBuf,
+    },
+}
+
+fn main() -> ExitCode {
+    let result = match Args::parse().command {
+        Command::Keys { count, format, edition, seed, target, charset, rng_seed } => {
+            let mut rng = rng_seed.map_or_else(fastrand::Rng::new, fastrand::Rng::with_seed);
+            match format {
+                KeyFormat::Legacy => generate_keys(count, seed, target, &charset, &mut rng),
+                KeyFormat::V2 => {
+                    for _ in 0..count {
+                        println!("{}", license_core::encode_v2_key(edition, rng.u32(..)));
+                    }
+                    Ok(())
+                }
+            }
+        }
+        Command::Licenses { licensees, count, edition, days, grace_days, seats, features, id_prefix, out_dir, signing_key } => {
+            let today = Local::now().date_naive();
+            let template = Claims {
+                license_id: String::new(),
+                licensee: String::new(),
+                edition,
+                issued: today,
+                expires: days.map(|days| today + Duration::days(days)),
+                seats,
+                features,
+                machine: None,
+                grace_days,
+            };
+            issue_licenses(&template, &licensees, count, &id_prefix, &out_dir, &signing_key)
+        }
+        Command::SigningKey { out } => create_signing_key(&out),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn generate_keys(count: usize, seed: u32, target: u32, charset: &str, rng: &mut fastrand::Rng) -> Result<(), String> {
+    let charset = parse_charset(charset)?;
+
+    // First half: "XXXX-XX", second half: "XX-XXXX".
+    let halves = (charset.len() as f64).powi(6);
+    let table_size = TABLE_SIZE.min(halves as usize);
+    let mut table: HashMap<u32, [u8; 7]> = HashMap::with_capacity(table_size);
+    for _ in 0..table_size * 4 {
+        if table.len() >= table_size {
+            break;
+        }
+        let half = random_half(&charset, 4, rng);
+        table.insert(license_core::key_hash(seed, &half), half);
+    }
+
+    let mut issued = HashSet::new();
+    let mut duplicates = 0;
+    while issued.len() < count {
+        if duplicates > 1000 {
+            return Err(format!("only {} distinct keys found with this charset", issued.len()));
+        }
+        let key = find_key(&table, target, &charset, rng).ok_or_else(|| {
+            format!(
+                "no key found after {} tries; target 0x{:08X} may be unsolvable with this charset",
+                MAX_TRIES, target
+            )
+        })?;
+        if seed == KEY_SEED && target == KEY_TARGET && license_core::verify_key(&key).is_err() {
+            return Err(format!("generated key {} was rejected by verify_key", key));
+        }
+        if issued.insert(key.clone()) {
+            println!("{}", key);
+        } else {
+            duplicates += 1;
+        }
+    }
+    Ok(())
+}
+
+fn find_key(table: &HashMap<u32, [u8; 7]>, target: u32, charset: &[u8], rng: &mut fastrand::Rng) -> Option<String> {
+    for _ in 0..MAX_TRIES {
+        let second = random_half(charset, 2, rng);
+        let Some(first) = table.get(&license_core::key_unhash(target, &second)) else {
+            continue;
+        };
+        let key: String = first.iter().chain(&second).map(|&b| b as char).collect();
+        if license_core::blocked_pattern(&key).is_none() {
+            return Some(key);
+        }
+    }
+    None
+}
+
+fn issue_licenses(
+    template: &Claims,
+    licensees: &[String],
+    count: usize,
+    id_prefix: &str,
+    out_dir: &Path,
+    signing_key: &Path,
+) -> Result<(), String> {
+    let key = load_signing_key(signing_key)?;
+    fs::create_dir_all(out_dir).map_err(|e| format!("cannot create {}: {}", out_dir.display(), e))?;
+
+    let date = template.issued.format("%Y%m%d");
+    let mut serial = 1;
+    for licensee in licensees {
+        for _ in 0..count {
+            let claims = Claims {
+                license_id: format!("{}-{}-{:04}", id_prefix, date, serial),
+                licensee: licensee.clone(),
+                ..template.clone()
+            };
+            let path = out_dir.join(format!("{}.cvlic", claims.license_id));
+            fs::write(&path, SignedLicense::sign(&claims, &key).to_json())
+                .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+            println!("{}", path.display());
+            serial += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Writes a fresh seed to `out`, refusing to replace an existing one, and its
+/// public key to `out` with a `.pub` extension.
+fn create_signing_key(out: &Path) -> Result<(), String> {
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed).map_err(|e| format!("cannot generate a seed: {}", e))?;
+    let public = hex::encode(SigningKey::from_bytes(&seed).verifying_key().to_bytes());
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(out).map_err(|e| format!("cannot create {}: {}", out.display(), e))?;
+    writeln!(file, "{}", hex::encode(seed)).map_err(|e| format!("cannot write {}: {}", out.display(), e))?;
+    let public_path = out.with_extension("pub");
+    fs::write(&public_path, format!("{}\n", public)).map_err(|e| format!("cannot write {}: {}", public_path.display(), e))?;
+
+    println!("seed:       {}", out.display());
+    println!("public key: {} ({})", public, public_path.display());
+    println!("build with CYBERVAULT_VENDOR_PUBLIC_KEY={} or --features dev-key", public);
+    Ok(())
+}
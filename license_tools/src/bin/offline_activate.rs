@@ -0,0 +1,80 @@
+//! Vendor portal tool: turns an offline activation request code into a
+//! response code bound to the requesting machine.
+//!
+//! ```text
+//! cargo run -p license_tools --bin offline_activate -- \
+//!     --request AEXM-... --licensee "Example Corp" --edition pro --days 365
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{Duration, Local};
+use clap::Parser;
+use license_core::{Claims, Edition, SignedLicense};
+use license_tools::{DEFAULT_SIGNING_KEY, load_signing_key};
+
+#[derive(Parser)]
+#[command(about = "Issue an offline activation response code")]
+struct Args {
+    /// Request code shown in the License Activation window
+    #[arg(long)]
+    request: String,
+    #[arg(long)]
+    licensee: String,
+    /// trial, pro or enterprise
+    #[arg(long, default_value = "pro")]
+    edition: Edition,
+    /// Days until expiry; omit for a perpetual license
+    #[arg(long)]
+    days: Option<i64>,
+    /// Days the app keeps working after expiry before turning read-only
+    #[arg(long)]
+    grace_days: Option<u32>,
+    #[arg(long, default_value_t = 1)]
+    seats: u32,
+    /// Extra feature to grant beyond the edition (repeatable)
+    #[arg(long = "feature")]
+    features: Vec<String>,
+    /// Defaults to CV-OFF-<machine id prefix>-<date>
+    #[arg(long)]
+    license_id: Option<String>,
+    #[arg(long, default_value = DEFAULT_SIGNING_KEY)]
+    signing_key: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(args) {
+        Ok(code) => {
+            println!("{}", code);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<String, String> {
+    let machine = license_core::parse_request_code(&args.request).map_err(|e| e.to_string())?;
+    // Kept with the activation record: it checks deactivation receipts later.
+    eprintln!("machine: {}", machine);
+    let key = load_signing_key(&args.signing_key)?;
+    let today = Local::now().date_naive();
+    let claims = Claims {
+        license_id: args
+            .license_id
+            .unwrap_or_else(|| format!("CV-OFF-{}-{}", &machine[..8], today.format("%Y%m%d"))),
+        licensee: args.licensee,
+        edition: args.edition,
+        issued: today,
+        expires: args.days.map(|days| today + Duration::days(days)),
+        seats: args.seats,
+        features: args.features,
+        machine: Some(machine),
+        grace_days: args.grace_days,
+    };
+    Ok(SignedLicense::sign(&claims, &key).to_code())
+}
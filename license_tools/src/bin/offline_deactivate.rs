@@ -0,0 +1,61 @@
+//! Vendor portal tool: reads an offline deactivation receipt so the seat can
+//! be reissued for another machine. `--machine` is the machine id recorded
+//! when the license was activated (from its request code or the activation
+//! server); only a receipt made on that machine is accepted.
+//!
+//! ```text
+//! cargo run -p license_tools --bin offline_deactivate -- \
+//!     --receipt AIXM-... --machine 3f2a... --license CV-OFF-1A2B3C4D-20260101
+//! ```
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use license_core::parse_deactivation_code;
+
+#[derive(Parser)]
+#[command(about = "Check an offline deactivation receipt")]
+struct Args {
+    /// Receipt shown by the app after deactivating without a connection
+    #[arg(long)]
+    receipt: String,
+    /// Machine ID the license was activated on
+    #[arg(long)]
+    machine: String,
+    /// Product key the customer says was removed
+    #[arg(long, conflicts_with = "license")]
+    key: Option<String>,
+    /// License ID the customer says was removed
+    #[arg(long)]
+    license: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let receipt = match parse_deactivation_code(&args.receipt) {
+        Ok(receipt) => receipt,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if !receipt.is_from_machine(&args.machine) {
+        println!("machine:     receipt was not made on {}", args.machine.trim());
+        return ExitCode::FAILURE;
+    }
+    println!("machine:     {}", args.machine.trim());
+    println!("deactivated: {}", receipt.date);
+
+    let matches = match (&args.key, &args.license) {
+        (Some(key), _) => receipt.is_for_key(key),
+        (None, Some(id)) => receipt.is_for_license_id(id),
+        (None, None) => return ExitCode::SUCCESS,
+    };
+    if matches {
+        println!("license:     matches, the seat can be reissued");
+        ExitCode::SUCCESS
+    } else {
+        println!("license:     does not match this receipt");
+        ExitCode::FAILURE
+    }
+}
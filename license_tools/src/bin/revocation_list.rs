@@ -0,0 +1,81 @@
+//! Vendor tool: signs a revocation list for distribution with the app.
+//!
+//! ```text
+//! cargo run -p license_tools --bin revocation_list -- \
+//!     --key ABCD-EFGH-JKLM --license CV-OFF-1A2B3C4D-20260101 --out revocations.cvrl
+//! ```
+//!
+//! With `--merge`, entries of an existing list are kept and the serial keeps
+//! growing.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::Local;
+use clap::Parser;
+use license_core::{RevocationList, SignedRevocationList};
+use license_tools::{DEFAULT_SIGNING_KEY, load_signing_key};
+
+#[derive(Parser)]
+#[command(about = "Sign a license revocation list")]
+struct Args {
+    /// Product key to revoke (repeatable); only its hash is published
+    #[arg(long = "key")]
+    keys: Vec<String>,
+    /// License ID to revoke (repeatable)
+    #[arg(long = "license")]
+    licenses: Vec<String>,
+    /// Existing signed list whose entries are carried over
+    #[arg(long)]
+    merge: Option<PathBuf>,
+    #[arg(long, default_value = "revocations.cvrl")]
+    out: PathBuf,
+    #[arg(long, default_value = DEFAULT_SIGNING_KEY)]
+    signing_key: PathBuf,
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(list) => {
+            println!("serial {}: {} keys, {} licenses", list.serial, list.keys.len(), list.licenses.len());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<RevocationList, String> {
+    let key = load_signing_key(&args.signing_key)?;
+    let mut list = match &args.merge {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            SignedRevocationList::parse(&text)
+                .and_then(|signed| signed.verify_with(&key.verifying_key()))
+                .map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        None => RevocationList::default(),
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    list.serial = now.max(list.serial + 1);
+    list.issued = Some(Local::now().date_naive());
+    for hash in args.keys.iter().map(|key| RevocationList::hash_key(key)) {
+        if !list.keys.contains(&hash) {
+            list.keys.push(hash);
+        }
+    }
+    for id in args.licenses {
+        if !list.licenses.contains(&id) {
+            list.licenses.push(id);
+        }
+    }
+
+    let signed = SignedRevocationList::sign(&list, &key);
+    fs::write(&args.out, signed.to_json()).map_err(|e| format!("cannot write {}: {}", args.out.display(), e))?;
+    Ok(list)
+}
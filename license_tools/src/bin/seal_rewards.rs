@@ -0,0 +1,64 @@
+//! Seals the rewards of a challenge pack, so that the pack only holds them
+//! as ciphertext that an accepted answer opens. Give one answer per level
+//! whose reward should be sealed; other levels keep a plain reward.
+//!
+//! ```text
+//! cargo run -p license_tools --bin seal_rewards -- draft.cvpack \
+//!     --answer crc=<answer> --answer rc4=<answer> --out packs/cybervault.cvpack
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use license_core::ChallengePack;
+
+#[derive(Parser)]
+#[command(about = "Seal challenge pack rewards under their levels' answers")]
+struct Args {
+    /// Pack with plain rewards
+    pack: PathBuf,
+    /// `LEVEL=ANSWER`, an answer the level accepts (repeatable)
+    #[arg(long = "answer", value_parser = parse_answer)]
+    answers: Vec<(String, String)>,
+    /// Where to write the sealed pack (default: the input file)
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match seal_pack(&args) {
+        Ok(sealed) => {
+            println!("sealed {} reward(s)", sealed);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn seal_pack(args: &Args) -> Result<usize, String> {
+    let text = fs::read_to_string(&args.pack).map_err(|e| format!("cannot read {}: {}", args.pack.display(), e))?;
+    let mut pack = ChallengePack::parse(&text).map_err(|e| format!("{}: {}", args.pack.display(), e))?;
+    for (id, answer) in &args.answers {
+        let level = pack
+            .levels
+            .iter_mut()
+            .find(|level| &level.id == id)
+            .ok_or_else(|| format!("the pack has no level '{}'", id))?;
+        level.seal_reward(answer).map_err(|e| e.to_string())?;
+    }
+    let out = args.out.as_ref().unwrap_or(&args.pack);
+    fs::write(out, pack.to_json() + "\n").map_err(|e| format!("cannot write {}: {}", out.display(), e))?;
+    Ok(args.answers.len())
+}
+
+fn parse_answer(text: &str) -> Result<(String, String), String> {
+    text.split_once('=')
+        .map(|(id, answer)| (id.to_string(), answer.to_string()))
+        .ok_or_else(|| "expected LEVEL=ANSWER".to_string())
+}
@@ -0,0 +1,43 @@
+//! Build step for `anti-debug-text` builds of the app: records the hash of
+//! the executable's `.text` section in the binary itself. Run it after every
+//! link, including after stripping.
+//!
+//! ```text
+//! cargo build --release -p license_gate --features hard,anti-debug
+//! cargo run -p license_tools --bin stamp_text -- target/release/license_gate
+//! ```
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(about = "Stamp the .text hash into a license_gate executable")]
+struct Args {
+    /// Executable to stamp in place
+    executable: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let path = &args.executable;
+    let result = fs::read(path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))
+        .and_then(|mut elf| {
+            let hash = license_core::stamp_text_hash(&mut elf)?;
+            fs::write(path, &elf).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+            Ok(hash)
+        });
+    match result {
+        Ok(hash) => {
+            println!("{}: .text {}", path.display(), hex::encode(hash));
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Shared helpers for the vendor-side command line tools.
+
+use std::fs;
+use std::path::Path;
+
+use ed25519_dalek::SigningKey;
+
+/// Default location of the development signing key, relative to the
+/// workspace root.
+pub const DEFAULT_SIGNING_KEY: &str = "keys/vendor_dev.key";
+
+/// Characters legacy keys are drawn from unless a tool is told otherwise.
+pub const DEFAULT_CHARSET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// The distinct printable characters of `charset` as `verify_key` sees them,
+/// sorted. Keys are normalized before they are checked, so letters are
+/// uppercased and dashes, which separate the key groups, are left out.
+pub fn parse_charset(charset: &str) -> Result<Vec<u8>, String> {
+    let mut charset: Vec<u8> = charset
+        .bytes()
+        .filter(|b| b.is_ascii_graphic() && *b != b'-')
+        .map(|b| b.to_ascii_uppercase())
+        .collect();
+    charset.sort_unstable();
+    charset.dedup();
+    if charset.is_empty() {
+        return Err("charset has no usable characters".to_string());
+    }
+    Ok(charset)
+}
+
+/// Half of a legacy key: seven characters from `charset` with a dash at
+/// `dash`. A key is a first half with the dash at 4 followed by a second half
+/// with the dash at 2.
+pub fn random_half(charset: &[u8], dash: usize, rng: &mut fastrand::Rng) -> [u8; 7] {
+    let mut half = [0u8; 7];
+    for (i, byte) in half.iter_mut().enumerate() {
+        *byte = if i == dash { b'-' } else { charset[rng.usize(..charset.len())] };
+    }
+    half
+}
+
+/// Parses a `u32` given in decimal or as `0x` hex, with optional `_`
+/// separators in hex.
+pub fn parse_u32(text: &str) -> Result<u32, String> {
+    let result = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(&hex.replace('_', ""), 16),
+        None => text.parse(),
+    };
+    result.map_err(|e| format!("{}: {}", text, e))
+}
+
+/// Reads a hex-encoded Ed25519 seed.
+pub fn load_signing_key(path: &Path) -> Result<SigningKey, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("cannot read signing key {}: {}", path.display(), e))?;
+    let seed: [u8; 32] = hex::decode(text.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("{} does not contain a 32-byte hex seed", path.display()))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Minimal JSON-over-HTTP helpers for the reference servers.
+pub mod http {
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+    use tiny_http::{Header, Request, Response};
+
+    pub fn read_json<T: DeserializeOwned>(request: &mut Request) -> Result<T, String> {
+        serde_json::from_reader(request.as_reader()).map_err(|e| format!("invalid request body: {}", e))
+    }
+
+    pub fn respond<T: Serialize>(request: Request, status: u16, body: &T) {
+        let body = serde_json::to_string(body).expect("response serializes to JSON");
+        let header = Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+        let _ = request.respond(Response::from_string(body).with_status_code(status).with_header(header));
+    }
+
+    pub fn respond_error(request: Request, status: u16, message: &str) {
+        respond(request, status, &license_core::protocol::ErrorResponse { error: message.to_string() });
+    }
+}